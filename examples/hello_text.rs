@@ -14,7 +14,7 @@ fn main() {
 
     let mut out = ScreenBuffer::new(FRAME_WIDTH, FRAME_HEIGHT);
     let video = TextVideoController::default();
-    video.render_frame(&ram, &mut out);
+    video.render_frame(&ram, 0, &mut out);
 
     println!(
         "Rendered frame_id={} at {}x{}",
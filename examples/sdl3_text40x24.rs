@@ -8,7 +8,9 @@ fn main() {
 mod app {
     use echo_lab::capture::CaptureOptions;
     use echo_lab::sdl_display_core::{run_text_display, SdlDisplayCoreOptions};
-    use echo_lab::video::{COLOR_BLACK, COLOR_WHITE};
+    use echo_lab::video::composite::VideoBackend;
+    use echo_lab::video::hires::HiresColor;
+    use echo_lab::video::{FrameGeometry, TextAddressing, COLOR_BLACK, COLOR_WHITE};
 
     struct CliOptions {
         config_path: String,
@@ -154,6 +156,11 @@ mod app {
             vsync_off: options.vsync_off,
             crossover_vsync_off: options.crossover_vsync_off,
             text_base: 0x0400,
+            // The demo pages are filled row after row.
+            text_addressing: TextAddressing::Linear,
+            geometry: FrameGeometry::default(),
+            hires_color: HiresColor::default(),
+            video: VideoBackend::default(),
             foreground_color: if options.white {
                 COLOR_WHITE
             } else {
@@ -161,11 +168,12 @@ mod app {
             },
         };
 
-        run_text_display(
-            core_options,
-            |ram| fill_text_page_demo_layout(ram, 0x0400),
-            |ram, guest_steps| state.update(ram, guest_steps),
-        )
+        let mut ram = [b' '; 65536];
+        fill_text_page_demo_layout(&mut ram, 0x0400);
+
+        run_text_display(core_options, ram, |ram, guest_steps| {
+            state.update(ram, guest_steps)
+        })
     }
 
     fn fill_text_page_demo_layout(ram: &mut [u8; 65536], text_base: usize) {
@@ -0,0 +1,40 @@
+use crate::video::VideoSwitches;
+use crate::video::log::VideoLog;
+
+pub trait Bus {
+    fn read(&mut self, addr: u16, cycle: u64) -> u8;
+    fn write(&mut self, addr: u16, value: u8, cycle: u64);
+    fn peek(&self, addr: u16) -> u8;
+
+    // Video fetches bypass the CPU's bank selection and read a RAM bank directly.
+    fn peek_display(&self, addr: u16, aux: bool) -> u8 {
+        let _ = aux;
+        self.peek(addr)
+    }
+
+    fn video_switches(&self) -> Option<VideoSwitches> {
+        None
+    }
+
+    // Recent display writes and switch changes, for beam-accurate rendering.
+    fn video_log(&self) -> Option<&VideoLog> {
+        None
+    }
+}
+
+impl Bus for [u8; 65536] {
+    #[inline]
+    fn read(&mut self, addr: u16, _cycle: u64) -> u8 {
+        self[addr as usize]
+    }
+
+    #[inline]
+    fn write(&mut self, addr: u16, value: u8, _cycle: u64) {
+        self[addr as usize] = value;
+    }
+
+    #[inline]
+    fn peek(&self, addr: u16) -> u8 {
+        self[addr as usize]
+    }
+}
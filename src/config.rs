@@ -1,6 +1,10 @@
+use crate::machines::ram_init::RamInit;
+use crate::video::composite::{Impairments, SAMPLES_PER_LINE};
+use std::fmt::Display;
 use std::fs;
 use std::io;
 use std::path::Path;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Sdl3Text40x24Config {
@@ -18,8 +22,24 @@ impl Default for Sdl3Text40x24Config {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SystemRomConfig {
+    pub path: Option<String>,
+    pub sha1: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MachineConfig {
+    pub ram_init: RamInit,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct EchoLabConfig {
     pub sdl3_text40x24: Sdl3Text40x24Config,
+    pub system_rom: SystemRomConfig,
+    pub machine: MachineConfig,
+    // Set only when the file has a [composite] section, so API-set impairments
+    // are otherwise left alone.
+    pub composite: Option<Impairments>,
 }
 
 impl EchoLabConfig {
@@ -76,6 +96,46 @@ impl EchoLabConfig {
                     }
                     _ => {}
                 }
+            } else if section == "system_rom" {
+                match key {
+                    "path" => cfg.system_rom.path = Some(value),
+                    "sha1" => cfg.system_rom.sha1 = Some(value),
+                    _ => {}
+                }
+            } else if section == "machine" && key == "ram_init" {
+                cfg.machine.ram_init = RamInit::parse(&value)
+                    .map_err(|e| format!("invalid ram_init on line {}: {}", line_no + 1, e))?;
+            } else if section == "composite" {
+                let impairments = cfg.composite.get_or_insert_with(Impairments::default);
+                let line_no = line_no + 1;
+                match key {
+                    "seed" => impairments.seed = parse_number(key, &value, line_no)?,
+                    "noise" => impairments.noise = parse_number(key, &value, line_no)?,
+                    "luma_bandwidth" => {
+                        impairments.luma_bandwidth = parse_number(key, &value, line_no)?;
+                    }
+                    "ghost_delay" => impairments.ghost_delay = parse_number(key, &value, line_no)?,
+                    "ghost_gain" => impairments.ghost_gain = parse_number(key, &value, line_no)?,
+                    "chroma_phase" => {
+                        impairments.chroma_phase = parse_number(key, &value, line_no)?;
+                    }
+                    "jitter" => {
+                        let jitter: usize = parse_number(key, &value, line_no)?;
+                        if jitter >= SAMPLES_PER_LINE {
+                            return Err(format!(
+                                "invalid jitter on line {}: must be below {} samples",
+                                line_no, SAMPLES_PER_LINE
+                            ));
+                        }
+                        impairments.jitter = jitter;
+                    }
+                    _ => {
+                        return Err(format!(
+                            "unknown [composite] key '{}' on line {}",
+                            key, line_no
+                        ));
+                    }
+                }
             }
         }
 
@@ -83,6 +143,14 @@ impl EchoLabConfig {
     }
 }
 
+fn parse_number<T: FromStr>(key: &str, raw: &str, line_no: usize) -> Result<T, String>
+where
+    T::Err: Display,
+{
+    raw.parse::<T>()
+        .map_err(|e| format!("invalid {} on line {}: {}", key, line_no, e))
+}
+
 fn parse_string_value(raw: &str, line_no: usize) -> Result<String, String> {
     if raw.starts_with('"') {
         if raw.len() < 2 || !raw.ends_with('"') {
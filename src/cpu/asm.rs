@@ -0,0 +1,539 @@
+use super::CpuVariant;
+use super::opcodes::{AddrMode, Op, Opcode};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    pub origin: u16,
+    pub bytes: Vec<u8>,
+    pub labels: HashMap<String, u16>,
+}
+
+impl Program {
+    pub fn end(&self) -> u16 {
+        self.origin.wrapping_add(self.bytes.len() as u16)
+    }
+}
+
+// Assembles 65C02 source in the usual syntax:
+//
+//   start:  LDX #$00        ; labels end with ':'
+//   loop    STA $0400,X     ; the colon is optional
+//           INX
+//           BNE loop
+//   count = 10              ; constants
+//           .byte 1, $02, 'A'
+//           .word start, <start, >start
+//
+// Numbers are decimal, `$hex` or `%binary`; `*` is the current address and
+// `<`/`>` take the low/high byte of an expression. Operands that resolve to
+// $00-$FF on the first pass use zero-page modes; forward references always
+// assemble as absolute.
+pub fn assemble(origin: u16, source: &str) -> Result<Program, String> {
+    Assembler::new().assemble(origin, source)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Assembler {
+    symbols: HashMap<String, u16>,
+    variant: CpuVariant,
+}
+
+#[derive(Debug, Clone)]
+enum Statement {
+    Instruction { op: Op, syntax: Syntax },
+    Bytes(Vec<String>),
+    Words(Vec<String>),
+    Org(String),
+}
+
+#[derive(Debug, Clone)]
+enum Syntax {
+    None,
+    Accumulator,
+    Immediate(String),
+    Direct(String),
+    DirectX(String),
+    DirectY(String),
+    Indirect(String),
+    IndirectX(String),
+    IndirectY(String),
+}
+
+struct Line {
+    number: usize,
+    addr: u16,
+    statement: Statement,
+    mode: Option<AddrMode>,
+}
+
+impl Assembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_symbol(mut self, name: &str, value: u16) -> Self {
+        self.symbols.insert(name.to_owned(), value);
+        self
+    }
+
+    // The NMOS variant accepts the undocumented mnemonics (LAX, SAX, DCP, ...)
+    // and drops the 65C02 additions.
+    pub fn with_variant(mut self, variant: CpuVariant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    pub fn assemble(&self, origin: u16, source: &str) -> Result<Program, String> {
+        let table = self.variant.opcodes();
+        let mut symbols = self.symbols.clone();
+        let mut labels = HashMap::new();
+        let mut lines = Vec::new();
+        let mut pc = origin as u32;
+
+        // Pass 1: collect labels and fix every instruction's size.
+        for (index, raw) in source.lines().enumerate() {
+            let number = index + 1;
+            let at = |e: String| format!("line {}: {}", number, e);
+
+            let Some(parsed) = parse_line(table, raw).map_err(at)? else {
+                continue;
+            };
+            if let Some(label) = &parsed.label {
+                let value = match &parsed.equate {
+                    Some(expr) => eval(expr, pc as u16, &symbols).map_err(at)?,
+                    None => pc as u16,
+                };
+                if symbols.insert(label.clone(), value).is_some() {
+                    return Err(at(format!("duplicate label '{}'", label)));
+                }
+                labels.insert(label.clone(), value);
+            }
+            let Some(statement) = parsed.statement else {
+                continue;
+            };
+
+            let mut mode = None;
+            let size = match &statement {
+                Statement::Instruction { op, syntax } => {
+                    let chosen =
+                        select_mode(table, *op, syntax, &symbols, pc as u16).map_err(at)?;
+                    mode = Some(chosen);
+                    1 + chosen.operand_len() as u32
+                }
+                Statement::Bytes(items) => items.iter().map(|item| byte_len(item)).sum(),
+                Statement::Words(items) => 2 * items.len() as u32,
+                Statement::Org(expr) => {
+                    let target = eval(expr, pc as u16, &symbols).map_err(at)? as u32;
+                    if target < pc {
+                        return Err(at(format!(".org ${:04X} moves backwards", target)));
+                    }
+                    target - pc
+                }
+            };
+            lines.push(Line {
+                number,
+                addr: pc as u16,
+                statement,
+                mode,
+            });
+            pc += size;
+            if pc > 0x1_0000 {
+                return Err(at("program runs past $FFFF".to_owned()));
+            }
+        }
+
+        // Pass 2: every symbol is known; emit bytes.
+        let mut bytes = Vec::with_capacity((pc - origin as u32) as usize);
+        for line in &lines {
+            let at = |e: String| format!("line {}: {}", line.number, e);
+            let value = |expr: &str| eval(expr, line.addr, &symbols).map_err(at);
+            match &line.statement {
+                Statement::Instruction { op, syntax } => {
+                    let mode = line.mode.unwrap_or(AddrMode::Implied);
+                    let opcode =
+                        encode(table, *op, mode).ok_or_else(|| at("bad mode".to_owned()))?;
+                    bytes.push(opcode);
+                    let Some(expr) = syntax.expr() else {
+                        continue;
+                    };
+                    let v = value(expr)?;
+                    match mode {
+                        AddrMode::Relative => {
+                            let offset = v as i32 - (line.addr as i32 + 2);
+                            if !(-128..=127).contains(&offset) {
+                                let e = format!("branch target ${:04X} out of range", v);
+                                return Err(at(e));
+                            }
+                            bytes.push(offset as u8);
+                        }
+                        _ if mode.operand_len() == 1 => {
+                            if v > 0xff {
+                                let e = format!("operand ${:04X} does not fit a byte", v);
+                                return Err(at(e));
+                            }
+                            bytes.push(v as u8);
+                        }
+                        _ => bytes.extend_from_slice(&v.to_le_bytes()),
+                    }
+                }
+                Statement::Bytes(items) => {
+                    for item in items {
+                        if let Some(text) = string_literal(item) {
+                            bytes.extend_from_slice(text.as_bytes());
+                            continue;
+                        }
+                        let v = value(item)?;
+                        if v > 0xff {
+                            return Err(at(format!(".byte value ${:04X} does not fit", v)));
+                        }
+                        bytes.push(v as u8);
+                    }
+                }
+                Statement::Words(items) => {
+                    for item in items {
+                        bytes.extend_from_slice(&value(item)?.to_le_bytes());
+                    }
+                }
+                Statement::Org(expr) => {
+                    let target = value(expr)?;
+                    bytes.resize(bytes.len() + (target - line.addr) as usize, 0);
+                }
+            }
+        }
+
+        Ok(Program {
+            origin,
+            bytes,
+            labels,
+        })
+    }
+
+    // Assembles a single instruction at `addr`, as a monitor's line assembler does.
+    pub fn assemble_line(&self, addr: u16, line: &str) -> Result<Vec<u8>, String> {
+        let program = self.assemble(addr, line)?;
+        Ok(program.bytes)
+    }
+}
+
+impl Syntax {
+    fn expr(&self) -> Option<&str> {
+        match self {
+            Syntax::None | Syntax::Accumulator => None,
+            Syntax::Immediate(e)
+            | Syntax::Direct(e)
+            | Syntax::DirectX(e)
+            | Syntax::DirectY(e)
+            | Syntax::Indirect(e)
+            | Syntax::IndirectX(e)
+            | Syntax::IndirectY(e) => Some(e),
+        }
+    }
+}
+
+struct ParsedLine {
+    label: Option<String>,
+    equate: Option<String>,
+    statement: Option<Statement>,
+}
+
+fn parse_line(table: &[Opcode; 256], raw: &str) -> Result<Option<ParsedLine>, String> {
+    let code = strip_comment(raw);
+    if code.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let mut rest = code.trim();
+    let mut label = None;
+    let mut equate = None;
+
+    if let Some((name, value)) = rest.split_once('=')
+        && is_identifier(name.trim())
+    {
+        return Ok(Some(ParsedLine {
+            label: Some(name.trim().to_owned()),
+            equate: Some(value.trim().to_owned()),
+            statement: None,
+        }));
+    }
+
+    let first = rest.split_whitespace().next().unwrap_or("");
+    if let Some(name) = first.strip_suffix(':') {
+        if !is_identifier(name) {
+            return Err(format!("invalid label '{}'", name));
+        }
+        label = Some(name.to_owned());
+        rest = rest[first.len()..].trim_start();
+    } else if !first.starts_with('.') && parse_mnemonic(table, first).is_none() {
+        // Without a colon, a label must be followed by nothing, `=`, an
+        // instruction or a directive; anything else is a mistyped mnemonic.
+        let after = rest[first.len()..].trim_start();
+        let next = after.split_whitespace().next().unwrap_or("");
+        let label_like = after.is_empty()
+            || after.starts_with('=')
+            || next.starts_with('.')
+            || parse_mnemonic(table, next).is_some();
+        if !label_like {
+            return Err(format!("unknown mnemonic '{}'", first));
+        }
+        if !is_identifier(first) {
+            return Err(format!("invalid label '{}'", first));
+        }
+        label = Some(first.to_owned());
+        rest = after;
+    }
+
+    if let Some(value) = rest.strip_prefix("=") {
+        equate = Some(value.trim().to_owned());
+        rest = "";
+    }
+
+    let statement = if rest.is_empty() {
+        None
+    } else {
+        Some(parse_statement(table, rest)?)
+    };
+    Ok(Some(ParsedLine {
+        label,
+        equate,
+        statement,
+    }))
+}
+
+fn parse_statement(table: &[Opcode; 256], text: &str) -> Result<Statement, String> {
+    let (word, operand) = match text.split_once(char::is_whitespace) {
+        Some((word, operand)) => (word, operand.trim()),
+        None => (text, ""),
+    };
+
+    match word.to_ascii_lowercase().as_str() {
+        ".byte" | ".db" => return Ok(Statement::Bytes(split_list(operand)?)),
+        ".word" | ".dw" => return Ok(Statement::Words(split_list(operand)?)),
+        ".org" => return Ok(Statement::Org(operand.to_owned())),
+        other if other.starts_with('.') => return Err(format!("unknown directive '{}'", word)),
+        _ => {}
+    }
+
+    let op = parse_mnemonic(table, word).ok_or_else(|| format!("unknown mnemonic '{}'", word))?;
+    Ok(Statement::Instruction {
+        op,
+        syntax: parse_operand(operand)?,
+    })
+}
+
+fn parse_operand(text: &str) -> Result<Syntax, String> {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let upper = compact.to_ascii_uppercase();
+    let inner = |s: &str, prefix: usize, suffix: usize| s[prefix..s.len() - suffix].to_owned();
+
+    let syntax = if compact.is_empty() {
+        Syntax::None
+    } else if upper == "A" {
+        Syntax::Accumulator
+    } else if let Some(expr) = compact.strip_prefix('#') {
+        Syntax::Immediate(expr.to_owned())
+    } else if compact.starts_with('(') && upper.ends_with(",X)") {
+        Syntax::IndirectX(inner(&compact, 1, 3))
+    } else if compact.starts_with('(') && upper.ends_with("),Y") {
+        Syntax::IndirectY(inner(&compact, 1, 3))
+    } else if compact.starts_with('(') && compact.ends_with(')') {
+        Syntax::Indirect(inner(&compact, 1, 1))
+    } else if upper.ends_with(",X") {
+        Syntax::DirectX(inner(&compact, 0, 2))
+    } else if upper.ends_with(",Y") {
+        Syntax::DirectY(inner(&compact, 0, 2))
+    } else {
+        Syntax::Direct(compact.clone())
+    };
+    if syntax.expr().is_some_and(str::is_empty) {
+        return Err(format!("missing operand in '{}'", text));
+    }
+    Ok(syntax)
+}
+
+fn select_mode(
+    table: &[Opcode; 256],
+    op: Op,
+    syntax: &Syntax,
+    symbols: &HashMap<String, u16>,
+    pc: u16,
+) -> Result<AddrMode, String> {
+    use AddrMode::*;
+
+    let supports = |mode: AddrMode| encode(table, op, mode).is_some();
+    let fits_zero_page = |expr: &str| matches!(eval(expr, pc, symbols), Ok(v) if v <= 0xff);
+    let pick = |zp: AddrMode, abs: AddrMode, expr: &str| {
+        if supports(zp) && (fits_zero_page(expr) || !supports(abs)) {
+            zp
+        } else {
+            abs
+        }
+    };
+
+    let mode = match syntax {
+        Syntax::None if supports(Implied) => Implied,
+        Syntax::None | Syntax::Accumulator => Accumulator,
+        Syntax::Immediate(_) => Immediate,
+        Syntax::Direct(_) if supports(Relative) => Relative,
+        Syntax::Direct(e) => pick(ZeroPage, Absolute, e),
+        Syntax::DirectX(e) => pick(ZeroPageX, AbsoluteX, e),
+        Syntax::DirectY(e) => pick(ZeroPageY, AbsoluteY, e),
+        Syntax::Indirect(_) if supports(Indirect) => Indirect,
+        Syntax::Indirect(_) => ZeroPageIndirect,
+        Syntax::IndirectX(_) if supports(AbsoluteIndexedIndirect) => AbsoluteIndexedIndirect,
+        Syntax::IndirectX(_) => IndexedIndirect,
+        Syntax::IndirectY(_) => IndirectIndexed,
+    };
+    if !supports(mode) {
+        return Err(format!(
+            "{} does not support {:?} addressing",
+            op.mnemonic(),
+            mode
+        ));
+    }
+    Ok(mode)
+}
+
+// The opcode table also lists undefined opcodes as NOPs; only $EA is the real one.
+fn encode(table: &[Opcode; 256], op: Op, mode: AddrMode) -> Option<u8> {
+    if op == Op::Nop {
+        return (mode == AddrMode::Implied).then_some(0xea);
+    }
+    table
+        .iter()
+        .position(|info| info.op == op && info.mode == mode)
+        .map(|index| index as u8)
+}
+
+fn parse_mnemonic(table: &[Opcode; 256], word: &str) -> Option<Op> {
+    table
+        .iter()
+        .map(|info| info.op)
+        .find(|op| op.mnemonic().eq_ignore_ascii_case(word))
+}
+
+fn eval(expr: &str, pc: u16, symbols: &HashMap<String, u16>) -> Result<u16, String> {
+    let expr = expr.trim();
+    if let Some(rest) = expr.strip_prefix('<') {
+        return Ok(eval(rest, pc, symbols)? & 0xff);
+    }
+    if let Some(rest) = expr.strip_prefix('>') {
+        return Ok(eval(rest, pc, symbols)? >> 8);
+    }
+
+    let mut total: i32 = 0;
+    let mut sign = 1;
+    let mut term = String::new();
+    let mut chars = expr.chars().peekable();
+    let mut terms = Vec::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                term.push(c);
+                if let Some(ch) = chars.next() {
+                    term.push(ch);
+                }
+                if chars.peek() == Some(&'\'') {
+                    term.push(chars.next().unwrap_or('\''));
+                }
+            }
+            '+' | '-' if !term.trim().is_empty() => {
+                terms.push((sign, std::mem::take(&mut term)));
+                sign = if c == '-' { -1 } else { 1 };
+            }
+            '-' => sign = -sign,
+            _ => term.push(c),
+        }
+    }
+    terms.push((sign, term));
+
+    for (sign, term) in terms {
+        total += sign * eval_term(term.trim(), pc, symbols)? as i32;
+    }
+    Ok(total as u16)
+}
+
+fn eval_term(term: &str, pc: u16, symbols: &HashMap<String, u16>) -> Result<u16, String> {
+    let parsed = if term == "*" {
+        Some(pc as u32)
+    } else if let Some(hex) = term.strip_prefix('$') {
+        u32::from_str_radix(hex, 16).ok()
+    } else if let Some(bin) = term.strip_prefix('%') {
+        u32::from_str_radix(bin, 2).ok()
+    } else if let Some(ch) = term.strip_prefix('\'') {
+        let ch = ch.strip_suffix('\'').unwrap_or(ch);
+        let mut chars = ch.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii() => Some(c as u32),
+            _ => None,
+        }
+    } else if term.starts_with(|c: char| c.is_ascii_digit()) {
+        term.parse().ok()
+    } else if is_identifier(term) {
+        return symbols
+            .get(term)
+            .copied()
+            .ok_or_else(|| format!("undefined label '{}'", term));
+    } else {
+        None
+    };
+    parsed
+        .and_then(|v| u16::try_from(v).ok())
+        .ok_or_else(|| format!("invalid value '{}'", term))
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn byte_len(item: &str) -> u32 {
+    string_literal(item).map_or(1, |s| s.len() as u32)
+}
+
+fn string_literal(item: &str) -> Option<&str> {
+    item.strip_prefix('"')?.strip_suffix('"')
+}
+
+// Splits a directive's operand on commas that are outside quotes.
+fn split_list(text: &str) -> Result<Vec<String>, String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    for c in text.chars() {
+        match (quote, c) {
+            (None, ',') => items.push(std::mem::take(&mut current).trim().to_owned()),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (Some(q), _) if q == c => {
+                quote = None;
+                current.push(c);
+            }
+            _ => current.push(c),
+        }
+    }
+    items.push(current.trim().to_owned());
+    if items.iter().any(String::is_empty) {
+        return Err(format!("empty item in '{}'", text));
+    }
+    Ok(items)
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (None, ';') => return &line[..i],
+            (None, '"') => quote = Some(c),
+            (None, '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            _ => {}
+        }
+    }
+    line
+}
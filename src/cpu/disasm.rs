@@ -0,0 +1,131 @@
+use super::CpuVariant;
+use super::opcodes::{AddrMode, Opcode};
+use crate::bus::Bus;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub addr: u16,
+    pub opcode: u8,
+    pub info: Opcode,
+    operand: [u8; 2],
+}
+
+impl Instruction {
+    pub fn size(&self) -> u16 {
+        self.info.size()
+    }
+
+    pub fn bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.opcode];
+        bytes.extend_from_slice(&self.operand[..self.info.mode.operand_len() as usize]);
+        bytes
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        self.info.op.mnemonic()
+    }
+
+    // Base cycle count from the opcode table; page-cross, branch and decimal
+    // penalties depend on run-time state and are not included.
+    pub fn cycles(&self) -> u8 {
+        self.info.cycles
+    }
+
+    // The operand as a number: the byte or word for most modes, the branch
+    // destination for relative mode.
+    pub fn operand_value(&self) -> Option<u16> {
+        let [lo, hi] = self.operand;
+        match self.info.mode.operand_len() {
+            0 => None,
+            1 if self.info.mode == AddrMode::Relative => Some(
+                self.addr
+                    .wrapping_add(2)
+                    .wrapping_add(lo as i8 as i16 as u16),
+            ),
+            1 => Some(lo as u16),
+            _ => Some(u16::from_le_bytes([lo, hi])),
+        }
+    }
+
+    pub fn operand(&self) -> String {
+        let value = self.operand_value().unwrap_or(0);
+        match self.info.mode {
+            AddrMode::Implied => String::new(),
+            AddrMode::Accumulator => "A".to_owned(),
+            AddrMode::Immediate => format!("#${:02X}", value),
+            AddrMode::ZeroPage => format!("${:02X}", value),
+            AddrMode::ZeroPageX => format!("${:02X},X", value),
+            AddrMode::ZeroPageY => format!("${:02X},Y", value),
+            AddrMode::Absolute | AddrMode::Relative => format!("${:04X}", value),
+            AddrMode::AbsoluteX => format!("${:04X},X", value),
+            AddrMode::AbsoluteY => format!("${:04X},Y", value),
+            AddrMode::Indirect => format!("(${:04X})", value),
+            AddrMode::IndexedIndirect => format!("(${:02X},X)", value),
+            AddrMode::IndirectIndexed => format!("(${:02X}),Y", value),
+            AddrMode::ZeroPageIndirect => format!("(${:02X})", value),
+            AddrMode::AbsoluteIndexedIndirect => format!("(${:04X},X)", value),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operand = self.operand();
+        if operand.is_empty() {
+            f.write_str(self.mnemonic())
+        } else {
+            write!(f, "{} {}", self.mnemonic(), operand)
+        }
+    }
+}
+
+pub fn decode(addr: u16, bytes: &[u8]) -> Instruction {
+    decode_for(CpuVariant::default(), addr, bytes)
+}
+
+// Missing operand bytes (a truncated slice) read as zero.
+pub fn decode_for(variant: CpuVariant, addr: u16, bytes: &[u8]) -> Instruction {
+    let byte = |i: usize| bytes.get(i).copied().unwrap_or(0);
+    let opcode = byte(0);
+    Instruction {
+        addr,
+        opcode,
+        info: variant.opcodes()[opcode as usize],
+        operand: [byte(1), byte(2)],
+    }
+}
+
+pub fn disassemble<B: Bus + ?Sized>(bus: &B, addr: u16) -> Instruction {
+    disassemble_for(CpuVariant::default(), bus, addr)
+}
+
+// Reads through `peek`, so disassembling I/O space never trips soft switches.
+pub fn disassemble_for<B: Bus + ?Sized>(variant: CpuVariant, bus: &B, addr: u16) -> Instruction {
+    let bytes = [
+        bus.peek(addr),
+        bus.peek(addr.wrapping_add(1)),
+        bus.peek(addr.wrapping_add(2)),
+    ];
+    decode_for(variant, addr, &bytes)
+}
+
+pub fn disassemble_range<B: Bus + ?Sized>(bus: &B, start: u16, count: usize) -> Vec<Instruction> {
+    disassemble_range_for(CpuVariant::default(), bus, start, count)
+}
+
+pub fn disassemble_range_for<B: Bus + ?Sized>(
+    variant: CpuVariant,
+    bus: &B,
+    start: u16,
+    count: usize,
+) -> Vec<Instruction> {
+    let mut addr = start;
+    let mut out = Vec::with_capacity(count);
+    for _ in 0..count {
+        let instruction = disassemble_for(variant, bus, addr);
+        addr = addr.wrapping_add(instruction.size());
+        out.push(instruction);
+    }
+    out
+}
@@ -0,0 +1,128 @@
+use super::{Cpu, CpuVariant};
+use std::fs;
+
+// Settings for Klaus Dormann-style functional test binaries: a memory image
+// whose tests end in `JMP *` (or a branch to itself) at a known success
+// address, and which keep the current test number at `test_case_addr`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionalTest {
+    pub load_addr: u16,
+    pub start_pc: u16,
+    pub success_pc: u16,
+    pub test_case_addr: u16,
+    pub max_cycles: u64,
+    pub variant: CpuVariant,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionalOutcome {
+    Passed { cycles: u64 },
+    Trapped { pc: u16, test_case: u8, cycles: u64 },
+    TimedOut { pc: u16, test_case: u8, cycles: u64 },
+}
+
+impl FunctionalOutcome {
+    pub fn passed(&self) -> bool {
+        matches!(self, FunctionalOutcome::Passed { .. })
+    }
+
+    pub fn describe(&self) -> String {
+        match *self {
+            FunctionalOutcome::Passed { cycles } => format!("passed after {} cycles", cycles),
+            FunctionalOutcome::Trapped {
+                pc,
+                test_case,
+                cycles,
+            } => format!(
+                "trapped at ${:04X} in test case ${:02X} after {} cycles",
+                pc, test_case, cycles
+            ),
+            FunctionalOutcome::TimedOut {
+                pc,
+                test_case,
+                cycles,
+            } => format!(
+                "no trap after {} cycles (PC=${:04X}, test case ${:02X})",
+                cycles, pc, test_case
+            ),
+        }
+    }
+}
+
+impl FunctionalTest {
+    // 6502_functional_test.bin as assembled with its default options, run on
+    // the NMOS core it was written for.
+    pub fn klaus_6502() -> Self {
+        Self {
+            load_addr: 0x0000,
+            start_pc: 0x0400,
+            success_pc: 0x3469,
+            test_case_addr: 0x0200,
+            max_cycles: 200_000_000,
+            variant: CpuVariant::Nmos6502,
+        }
+    }
+
+    // 65C02_extended_opcodes_test.bin with Rockwell/WDC bit opcodes disabled,
+    // matching the IIe's 65C02.
+    pub fn klaus_65c02_extended() -> Self {
+        Self {
+            success_pc: 0x24f1,
+            variant: CpuVariant::Cmos65C02,
+            ..Self::klaus_6502()
+        }
+    }
+
+    pub fn with_success_pc(mut self, pc: u16) -> Self {
+        self.success_pc = pc;
+        self
+    }
+
+    pub fn with_variant(mut self, variant: CpuVariant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    pub fn run_path(&self, path: &str) -> Result<FunctionalOutcome, String> {
+        let image = fs::read(path).map_err(|e| format!("failed to read '{}': {}", path, e))?;
+        self.run(&image)
+    }
+
+    pub fn run(&self, image: &[u8]) -> Result<FunctionalOutcome, String> {
+        let start = self.load_addr as usize;
+        if start + image.len() > 0x1_0000 {
+            return Err(format!(
+                "test image ({} bytes) does not fit at ${:04X}",
+                image.len(),
+                self.load_addr
+            ));
+        }
+        let mut mem = [0u8; 65536];
+        mem[start..start + image.len()].copy_from_slice(image);
+
+        let mut cpu = Cpu::new().with_variant(self.variant);
+        cpu.pc = self.start_pc;
+        while cpu.cycles < self.max_cycles {
+            let pc = cpu.pc;
+            cpu.step(&mut mem);
+            if cpu.pc != pc {
+                continue;
+            }
+            let cycles = cpu.cycles;
+            if pc == self.success_pc {
+                return Ok(FunctionalOutcome::Passed { cycles });
+            }
+            return Ok(FunctionalOutcome::Trapped {
+                pc,
+                test_case: mem[self.test_case_addr as usize],
+                cycles,
+            });
+        }
+
+        Ok(FunctionalOutcome::TimedOut {
+            pc: cpu.pc,
+            test_case: mem[self.test_case_addr as usize],
+            cycles: cpu.cycles,
+        })
+    }
+}
@@ -0,0 +1,915 @@
+pub mod asm;
+pub mod disasm;
+pub mod functional;
+pub mod opcodes;
+
+use crate::bus::Bus;
+use opcodes::{AddrMode, CMOS_OPCODES, NMOS_OPCODES, Op, Opcode};
+
+pub const FLAG_C: u8 = 0x01;
+pub const FLAG_Z: u8 = 0x02;
+pub const FLAG_I: u8 = 0x04;
+pub const FLAG_D: u8 = 0x08;
+pub const FLAG_B: u8 = 0x10;
+pub const FLAG_U: u8 = 0x20;
+pub const FLAG_V: u8 = 0x40;
+pub const FLAG_N: u8 = 0x80;
+
+pub const STACK_BASE: u16 = 0x0100;
+pub const NMI_VECTOR: u16 = 0xfffa;
+pub const RESET_VECTOR: u16 = 0xfffc;
+pub const IRQ_VECTOR: u16 = 0xfffe;
+pub const INTERRUPT_CYCLES: u32 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CpuVariant {
+    // Apple II/II+: undocumented opcodes, binary-derived decimal flags, JMP ($xxFF) bug.
+    Nmos6502,
+    // Apple IIe (enhanced): undefined opcodes are NOPs, decimal mode costs a cycle.
+    #[default]
+    Cmos65C02,
+}
+
+impl CpuVariant {
+    pub fn opcodes(self) -> &'static [Opcode; 256] {
+        match self {
+            CpuVariant::Nmos6502 => &NMOS_OPCODES,
+            CpuVariant::Cmos65C02 => &CMOS_OPCODES,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CpuVariant::Nmos6502 => "6502",
+            CpuVariant::Cmos65C02 => "65C02",
+        }
+    }
+}
+
+// Magic constant for the unstable ANE/LXA opcodes; it varies between chips.
+const NMOS_MAGIC: u8 = 0xee;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cpu {
+    pub a: u8,
+    pub x: u8,
+    pub y: u8,
+    pub sp: u8,
+    pub pc: u16,
+    pub p: u8,
+    pub cycles: u64,
+    variant: CpuVariant,
+    jammed: bool,
+    bus_cycle: u64,
+    irq_lines: u32,
+    nmi_line: bool,
+    nmi_pending: bool,
+}
+
+impl Default for Cpu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cpu {
+    pub fn new() -> Self {
+        Self {
+            a: 0,
+            x: 0,
+            y: 0,
+            sp: 0xfd,
+            pc: 0,
+            p: FLAG_U | FLAG_I,
+            cycles: 0,
+            variant: CpuVariant::default(),
+            jammed: false,
+            bus_cycle: 0,
+            irq_lines: 0,
+            nmi_line: false,
+            nmi_pending: false,
+        }
+    }
+
+    pub fn with_variant(mut self, variant: CpuVariant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    pub fn variant(&self) -> CpuVariant {
+        self.variant
+    }
+
+    // An NMOS JAM opcode stops the CPU until the next RESET.
+    pub fn jammed(&self) -> bool {
+        self.jammed
+    }
+
+    pub fn flag(&self, mask: u8) -> bool {
+        (self.p & mask) != 0
+    }
+
+    pub fn set_flag(&mut self, mask: u8, on: bool) {
+        if on {
+            self.p |= mask;
+        } else {
+            self.p &= !mask;
+        }
+    }
+
+    pub fn decode(&self, opcode: u8) -> &'static Opcode {
+        &self.variant.opcodes()[opcode as usize]
+    }
+
+    // RESET keeps A/X/Y, drops SP by three without writing, and loads PC from $FFFC.
+    // Only the 65C02 clears D; the NMOS part leaves it as it was.
+    pub fn reset<B: Bus + ?Sized>(&mut self, bus: &mut B) -> u32 {
+        self.bus_cycle = self.cycles;
+        self.sp = self.sp.wrapping_sub(3);
+        self.set_flag(FLAG_I, true);
+        if self.is_cmos() {
+            self.set_flag(FLAG_D, false);
+        }
+        self.p |= FLAG_U;
+        self.nmi_pending = false;
+        self.jammed = false;
+        self.pc = self.read_word(bus, RESET_VECTOR);
+        self.cycles += INTERRUPT_CYCLES as u64;
+        INTERRUPT_CYCLES
+    }
+
+    // IRQ is level-triggered and wired-OR: each source holds its own line bit
+    // until it deasserts.
+    pub fn set_irq_line(&mut self, line: u8, asserted: bool) {
+        let mask = 1u32 << (line & 31);
+        if asserted {
+            self.irq_lines |= mask;
+        } else {
+            self.irq_lines &= !mask;
+        }
+    }
+
+    pub fn irq_asserted(&self) -> bool {
+        self.irq_lines != 0
+    }
+
+    // NMI is edge-triggered: only a low-to-high transition latches a request.
+    pub fn set_nmi_line(&mut self, asserted: bool) {
+        if asserted && !self.nmi_line {
+            self.nmi_pending = true;
+        }
+        self.nmi_line = asserted;
+    }
+
+    pub fn nmi_pending(&self) -> bool {
+        self.nmi_pending
+    }
+
+    pub fn step<B: Bus + ?Sized>(&mut self, bus: &mut B) -> u32 {
+        self.bus_cycle = self.cycles;
+
+        // A jammed CPU ignores interrupts; time still passes.
+        if self.jammed {
+            self.cycles += 1;
+            return 1;
+        }
+
+        if self.nmi_pending {
+            self.nmi_pending = false;
+            return self.interrupt(bus, NMI_VECTOR);
+        }
+        if self.irq_asserted() && !self.flag(FLAG_I) {
+            return self.interrupt(bus, IRQ_VECTOR);
+        }
+
+        let opcode = self.fetch(bus);
+        let info = *self.decode(opcode);
+        let mut cycles = info.cycles as u32;
+
+        match info.op {
+            Op::Lda => {
+                let (v, extra) = self.read_operand(bus, info.mode);
+                self.a = v;
+                self.set_nz(v);
+                cycles += extra;
+            }
+            Op::Ldx => {
+                let (v, extra) = self.read_operand(bus, info.mode);
+                self.x = v;
+                self.set_nz(v);
+                cycles += extra;
+            }
+            Op::Ldy => {
+                let (v, extra) = self.read_operand(bus, info.mode);
+                self.y = v;
+                self.set_nz(v);
+                cycles += extra;
+            }
+            Op::Sta => {
+                let (addr, _) = self.operand_addr(bus, info.mode);
+                self.write(bus, addr, self.a);
+            }
+            Op::Stx => {
+                let (addr, _) = self.operand_addr(bus, info.mode);
+                self.write(bus, addr, self.x);
+            }
+            Op::Sty => {
+                let (addr, _) = self.operand_addr(bus, info.mode);
+                self.write(bus, addr, self.y);
+            }
+            Op::Stz => {
+                let (addr, _) = self.operand_addr(bus, info.mode);
+                self.write(bus, addr, 0);
+            }
+            Op::Adc => {
+                let (v, extra) = self.read_operand(bus, info.mode);
+                cycles += extra + self.add(v);
+            }
+            Op::Sbc => {
+                let (v, extra) = self.read_operand(bus, info.mode);
+                cycles += extra + self.subtract(v);
+            }
+            Op::And => {
+                let (v, extra) = self.read_operand(bus, info.mode);
+                self.a &= v;
+                self.set_nz(self.a);
+                cycles += extra;
+            }
+            Op::Ora => {
+                let (v, extra) = self.read_operand(bus, info.mode);
+                self.a |= v;
+                self.set_nz(self.a);
+                cycles += extra;
+            }
+            Op::Eor => {
+                let (v, extra) = self.read_operand(bus, info.mode);
+                self.a ^= v;
+                self.set_nz(self.a);
+                cycles += extra;
+            }
+            Op::Cmp => {
+                let (v, extra) = self.read_operand(bus, info.mode);
+                self.compare(self.a, v);
+                cycles += extra;
+            }
+            Op::Cpx => {
+                let (v, extra) = self.read_operand(bus, info.mode);
+                self.compare(self.x, v);
+                cycles += extra;
+            }
+            Op::Cpy => {
+                let (v, extra) = self.read_operand(bus, info.mode);
+                self.compare(self.y, v);
+                cycles += extra;
+            }
+            Op::Bit => {
+                let (v, extra) = self.read_operand(bus, info.mode);
+                self.set_flag(FLAG_Z, (self.a & v) == 0);
+                // BIT #imm only touches Z on the 65C02.
+                if info.mode != AddrMode::Immediate {
+                    self.set_flag(FLAG_N, (v & 0x80) != 0);
+                    self.set_flag(FLAG_V, (v & 0x40) != 0);
+                }
+                cycles += extra;
+            }
+            Op::Trb | Op::Tsb => {
+                let (addr, _) = self.operand_addr(bus, info.mode);
+                let v = self.read(bus, addr);
+                self.set_flag(FLAG_Z, (self.a & v) == 0);
+                let result = if info.op == Op::Tsb {
+                    v | self.a
+                } else {
+                    v & !self.a
+                };
+                self.read(bus, addr);
+                self.write(bus, addr, result);
+            }
+            Op::Asl | Op::Lsr | Op::Rol | Op::Ror | Op::Inc | Op::Dec => {
+                cycles += self.read_modify_write(bus, info);
+            }
+            Op::Inx => {
+                self.x = self.x.wrapping_add(1);
+                self.set_nz(self.x);
+            }
+            Op::Iny => {
+                self.y = self.y.wrapping_add(1);
+                self.set_nz(self.y);
+            }
+            Op::Dex => {
+                self.x = self.x.wrapping_sub(1);
+                self.set_nz(self.x);
+            }
+            Op::Dey => {
+                self.y = self.y.wrapping_sub(1);
+                self.set_nz(self.y);
+            }
+            Op::Tax => {
+                self.x = self.a;
+                self.set_nz(self.x);
+            }
+            Op::Tay => {
+                self.y = self.a;
+                self.set_nz(self.y);
+            }
+            Op::Txa => {
+                self.a = self.x;
+                self.set_nz(self.a);
+            }
+            Op::Tya => {
+                self.a = self.y;
+                self.set_nz(self.a);
+            }
+            Op::Tsx => {
+                self.x = self.sp;
+                self.set_nz(self.x);
+            }
+            Op::Txs => self.sp = self.x,
+            Op::Pha => self.push(bus, self.a),
+            Op::Phx => self.push(bus, self.x),
+            Op::Phy => self.push(bus, self.y),
+            Op::Php => self.push(bus, self.p | FLAG_B | FLAG_U),
+            Op::Pla => {
+                self.a = self.pull(bus);
+                self.set_nz(self.a);
+            }
+            Op::Plx => {
+                self.x = self.pull(bus);
+                self.set_nz(self.x);
+            }
+            Op::Ply => {
+                self.y = self.pull(bus);
+                self.set_nz(self.y);
+            }
+            Op::Plp => {
+                let v = self.pull(bus);
+                self.p = (v & !FLAG_B) | FLAG_U;
+            }
+            Op::Clc => self.set_flag(FLAG_C, false),
+            Op::Sec => self.set_flag(FLAG_C, true),
+            Op::Cli => self.set_flag(FLAG_I, false),
+            Op::Sei => self.set_flag(FLAG_I, true),
+            Op::Cld => self.set_flag(FLAG_D, false),
+            Op::Sed => self.set_flag(FLAG_D, true),
+            Op::Clv => self.set_flag(FLAG_V, false),
+            Op::Bpl => cycles += self.branch(bus, !self.flag(FLAG_N)),
+            Op::Bmi => cycles += self.branch(bus, self.flag(FLAG_N)),
+            Op::Bvc => cycles += self.branch(bus, !self.flag(FLAG_V)),
+            Op::Bvs => cycles += self.branch(bus, self.flag(FLAG_V)),
+            Op::Bcc => cycles += self.branch(bus, !self.flag(FLAG_C)),
+            Op::Bcs => cycles += self.branch(bus, self.flag(FLAG_C)),
+            Op::Bne => cycles += self.branch(bus, !self.flag(FLAG_Z)),
+            Op::Beq => cycles += self.branch(bus, self.flag(FLAG_Z)),
+            Op::Bra => {
+                // BRA's table timing already includes the taken cycle.
+                cycles += self.branch(bus, true) - 1;
+            }
+            Op::Jmp => {
+                let (addr, _) = self.operand_addr(bus, info.mode);
+                self.pc = addr;
+            }
+            Op::Jsr => {
+                let lo = self.fetch(bus) as u16;
+                let ret = self.pc;
+                self.push(bus, (ret >> 8) as u8);
+                self.push(bus, ret as u8);
+                let hi = self.fetch(bus) as u16;
+                self.pc = (hi << 8) | lo;
+            }
+            Op::Rts => {
+                let lo = self.pull(bus) as u16;
+                let hi = self.pull(bus) as u16;
+                self.pc = ((hi << 8) | lo).wrapping_add(1);
+            }
+            Op::Rti => {
+                let v = self.pull(bus);
+                self.p = (v & !FLAG_B) | FLAG_U;
+                let lo = self.pull(bus) as u16;
+                let hi = self.pull(bus) as u16;
+                self.pc = (hi << 8) | lo;
+            }
+            Op::Brk => {
+                let ret = self.pc.wrapping_add(1);
+                self.push(bus, (ret >> 8) as u8);
+                self.push(bus, ret as u8);
+                self.push(bus, self.p | FLAG_B | FLAG_U);
+                self.set_flag(FLAG_I, true);
+                if self.is_cmos() {
+                    self.set_flag(FLAG_D, false);
+                }
+                self.pc = self.read_word(bus, IRQ_VECTOR);
+            }
+            Op::Nop if self.is_cmos() || info.mode == AddrMode::Implied => {
+                self.pc = self.pc.wrapping_add(info.mode.operand_len());
+            }
+            Op::Nop => {
+                // NMOS NOPs with operands perform the read, page-cross penalty included.
+                let (_, extra) = self.read_operand(bus, info.mode);
+                cycles += extra;
+            }
+            _ => cycles += self.undocumented(bus, info),
+        }
+
+        self.cycles += cycles as u64;
+        cycles
+    }
+
+    fn interrupt<B: Bus + ?Sized>(&mut self, bus: &mut B, vector: u16) -> u32 {
+        let ret = self.pc;
+        self.push(bus, (ret >> 8) as u8);
+        self.push(bus, ret as u8);
+        self.push(bus, (self.p & !FLAG_B) | FLAG_U);
+        self.set_flag(FLAG_I, true);
+        if self.is_cmos() {
+            self.set_flag(FLAG_D, false);
+        }
+        self.pc = self.read_word(bus, vector);
+        self.cycles += INTERRUPT_CYCLES as u64;
+        INTERRUPT_CYCLES
+    }
+
+    // Each bus access is stamped with the next cycle of the current instruction.
+    fn read<B: Bus + ?Sized>(&mut self, bus: &mut B, addr: u16) -> u8 {
+        let v = bus.read(addr, self.bus_cycle);
+        self.bus_cycle += 1;
+        v
+    }
+
+    fn write<B: Bus + ?Sized>(&mut self, bus: &mut B, addr: u16, value: u8) {
+        bus.write(addr, value, self.bus_cycle);
+        self.bus_cycle += 1;
+    }
+
+    fn fetch<B: Bus + ?Sized>(&mut self, bus: &mut B) -> u8 {
+        let v = self.read(bus, self.pc);
+        self.pc = self.pc.wrapping_add(1);
+        v
+    }
+
+    fn fetch_word<B: Bus + ?Sized>(&mut self, bus: &mut B) -> u16 {
+        let lo = self.fetch(bus) as u16;
+        let hi = self.fetch(bus) as u16;
+        (hi << 8) | lo
+    }
+
+    fn read_word<B: Bus + ?Sized>(&mut self, bus: &mut B, addr: u16) -> u16 {
+        let lo = self.read(bus, addr) as u16;
+        let hi = self.read(bus, addr.wrapping_add(1)) as u16;
+        (hi << 8) | lo
+    }
+
+    fn read_zp_word<B: Bus + ?Sized>(&mut self, bus: &mut B, zp: u8) -> u16 {
+        let lo = self.read(bus, zp as u16) as u16;
+        let hi = self.read(bus, zp.wrapping_add(1) as u16) as u16;
+        (hi << 8) | lo
+    }
+
+    fn push<B: Bus + ?Sized>(&mut self, bus: &mut B, value: u8) {
+        self.write(bus, STACK_BASE | self.sp as u16, value);
+        self.sp = self.sp.wrapping_sub(1);
+    }
+
+    fn pull<B: Bus + ?Sized>(&mut self, bus: &mut B) -> u8 {
+        self.sp = self.sp.wrapping_add(1);
+        self.read(bus, STACK_BASE | self.sp as u16)
+    }
+
+    fn set_nz(&mut self, v: u8) {
+        self.set_flag(FLAG_Z, v == 0);
+        self.set_flag(FLAG_N, (v & 0x80) != 0);
+    }
+
+    // Returns the effective address and whether indexing crossed a page.
+    fn operand_addr<B: Bus + ?Sized>(&mut self, bus: &mut B, mode: AddrMode) -> (u16, bool) {
+        match mode {
+            AddrMode::ZeroPage => (self.fetch(bus) as u16, false),
+            AddrMode::ZeroPageX => (self.fetch(bus).wrapping_add(self.x) as u16, false),
+            AddrMode::ZeroPageY => (self.fetch(bus).wrapping_add(self.y) as u16, false),
+            AddrMode::Absolute => (self.fetch_word(bus), false),
+            AddrMode::AbsoluteX => {
+                let base = self.fetch_word(bus);
+                let addr = base.wrapping_add(self.x as u16);
+                (addr, (base & 0xff00) != (addr & 0xff00))
+            }
+            AddrMode::AbsoluteY => {
+                let base = self.fetch_word(bus);
+                let addr = base.wrapping_add(self.y as u16);
+                (addr, (base & 0xff00) != (addr & 0xff00))
+            }
+            AddrMode::Indirect => {
+                // The NMOS part fetches the high byte of JMP ($xxFF) from $xx00;
+                // the 65C02 fixes the page wrap.
+                let ptr = self.fetch_word(bus);
+                if self.is_cmos() {
+                    (self.read_word(bus, ptr), false)
+                } else {
+                    let lo = self.read(bus, ptr) as u16;
+                    let hi_addr = (ptr & 0xff00) | (ptr.wrapping_add(1) & 0x00ff);
+                    let hi = self.read(bus, hi_addr) as u16;
+                    ((hi << 8) | lo, false)
+                }
+            }
+            AddrMode::IndexedIndirect => {
+                let zp = self.fetch(bus).wrapping_add(self.x);
+                (self.read_zp_word(bus, zp), false)
+            }
+            AddrMode::IndirectIndexed => {
+                let zp = self.fetch(bus);
+                let base = self.read_zp_word(bus, zp);
+                let addr = base.wrapping_add(self.y as u16);
+                (addr, (base & 0xff00) != (addr & 0xff00))
+            }
+            AddrMode::ZeroPageIndirect => {
+                let zp = self.fetch(bus);
+                (self.read_zp_word(bus, zp), false)
+            }
+            AddrMode::AbsoluteIndexedIndirect => {
+                let ptr = self.fetch_word(bus).wrapping_add(self.x as u16);
+                (self.read_word(bus, ptr), false)
+            }
+            AddrMode::Immediate => {
+                let addr = self.pc;
+                self.pc = self.pc.wrapping_add(1);
+                (addr, false)
+            }
+            AddrMode::Implied | AddrMode::Accumulator | AddrMode::Relative => {
+                unreachable!("addressing mode {:?} has no effective address", mode)
+            }
+        }
+    }
+
+    // Reads an instruction's operand, returning the value and any page-cross penalty.
+    fn read_operand<B: Bus + ?Sized>(&mut self, bus: &mut B, mode: AddrMode) -> (u8, u32) {
+        let (addr, crossed) = self.operand_addr(bus, mode);
+        let v = self.read(bus, addr);
+        let penalty = match mode {
+            AddrMode::AbsoluteX | AddrMode::AbsoluteY | AddrMode::IndirectIndexed if crossed => 1,
+            _ => 0,
+        };
+        (v, penalty)
+    }
+
+    fn read_modify_write<B: Bus + ?Sized>(&mut self, bus: &mut B, info: Opcode) -> u32 {
+        if info.mode == AddrMode::Accumulator {
+            self.a = self.modify(info.op, self.a);
+            return 0;
+        }
+
+        let (addr, crossed) = self.operand_addr(bus, info.mode);
+        self.modify_memory(bus, addr, info.op);
+
+        // 65C02 shifts and rotates on abs,X only pay for page crossings; INC/DEC
+        // and every NMOS abs,X RMW always take 7.
+        let shift = matches!(info.op, Op::Asl | Op::Lsr | Op::Rol | Op::Ror);
+        if self.is_cmos() && shift && info.mode == AddrMode::AbsoluteX && crossed {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn modify_memory<B: Bus + ?Sized>(&mut self, bus: &mut B, addr: u16, op: Op) -> u8 {
+        let v = self.read(bus, addr);
+        // The NMOS part writes the unmodified value back before the result;
+        // the 65C02 re-reads the operand instead.
+        if self.is_cmos() {
+            self.read(bus, addr);
+        } else {
+            self.write(bus, addr, v);
+        }
+        let result = self.modify(op, v);
+        self.write(bus, addr, result);
+        result
+    }
+
+    // NMOS-only opcodes; the 65C02 table never decodes to these.
+    fn undocumented<B: Bus + ?Sized>(&mut self, bus: &mut B, info: Opcode) -> u32 {
+        match info.op {
+            Op::Slo | Op::Rla | Op::Sre | Op::Rra | Op::Dcp | Op::Isc => {
+                let base = match info.op {
+                    Op::Slo => Op::Asl,
+                    Op::Rla => Op::Rol,
+                    Op::Sre => Op::Lsr,
+                    Op::Rra => Op::Ror,
+                    Op::Dcp => Op::Dec,
+                    _ => Op::Inc,
+                };
+                let (addr, _) = self.operand_addr(bus, info.mode);
+                let v = self.modify_memory(bus, addr, base);
+                match info.op {
+                    Op::Slo => self.a |= v,
+                    Op::Rla => self.a &= v,
+                    Op::Sre => self.a ^= v,
+                    Op::Rra => {
+                        self.add(v);
+                        return 0;
+                    }
+                    Op::Dcp => {
+                        self.compare(self.a, v);
+                        return 0;
+                    }
+                    _ => {
+                        self.subtract(v);
+                        return 0;
+                    }
+                }
+                self.set_nz(self.a);
+                0
+            }
+            Op::Sax => {
+                let (addr, _) = self.operand_addr(bus, info.mode);
+                self.write(bus, addr, self.a & self.x);
+                0
+            }
+            Op::Lax => {
+                let (v, extra) = self.read_operand(bus, info.mode);
+                self.a = v;
+                self.x = v;
+                self.set_nz(v);
+                extra
+            }
+            Op::Las => {
+                let (v, extra) = self.read_operand(bus, info.mode);
+                let v = v & self.sp;
+                self.a = v;
+                self.x = v;
+                self.sp = v;
+                self.set_nz(v);
+                extra
+            }
+            Op::Anc => {
+                let (v, _) = self.read_operand(bus, info.mode);
+                self.a &= v;
+                self.set_nz(self.a);
+                self.set_flag(FLAG_C, (self.a & 0x80) != 0);
+                0
+            }
+            Op::Alr => {
+                let (v, _) = self.read_operand(bus, info.mode);
+                self.a = self.modify(Op::Lsr, self.a & v);
+                0
+            }
+            Op::Arr => {
+                let (v, _) = self.read_operand(bus, info.mode);
+                self.arr(v);
+                0
+            }
+            Op::Sbx => {
+                let (v, _) = self.read_operand(bus, info.mode);
+                let ax = self.a & self.x;
+                self.set_flag(FLAG_C, ax >= v);
+                self.x = ax.wrapping_sub(v);
+                self.set_nz(self.x);
+                0
+            }
+            Op::Ane => {
+                let (v, _) = self.read_operand(bus, info.mode);
+                self.a = (self.a | NMOS_MAGIC) & self.x & v;
+                self.set_nz(self.a);
+                0
+            }
+            Op::Lxa => {
+                let (v, _) = self.read_operand(bus, info.mode);
+                self.a = (self.a | NMOS_MAGIC) & v;
+                self.x = self.a;
+                self.set_nz(self.a);
+                0
+            }
+            Op::Sha | Op::Shx | Op::Shy | Op::Tas => {
+                let (addr, crossed) = self.operand_addr(bus, info.mode);
+                let v = match info.op {
+                    Op::Sha => self.a & self.x,
+                    Op::Shx => self.x,
+                    Op::Shy => self.y,
+                    _ => {
+                        self.sp = self.a & self.x;
+                        self.sp
+                    }
+                };
+                self.store_and_high(bus, addr, crossed, v);
+                0
+            }
+            Op::Jam => {
+                self.jammed = true;
+                self.pc = self.pc.wrapping_sub(1);
+                0
+            }
+            _ => unreachable!("{:?} is handled by step", info.op),
+        }
+    }
+
+    // SHA/SHX/SHY/TAS store the value ANDed with the base address high byte
+    // plus one; on a page cross that value also replaces the high byte.
+    fn store_and_high<B: Bus + ?Sized>(&mut self, bus: &mut B, addr: u16, crossed: bool, v: u8) {
+        let base_hi = ((addr >> 8) as u8).wrapping_sub(crossed as u8);
+        let value = v & base_hi.wrapping_add(1);
+        let target = if crossed {
+            ((value as u16) << 8) | (addr & 0x00ff)
+        } else {
+            addr
+        };
+        self.write(bus, target, value);
+    }
+
+    fn arr(&mut self, v: u8) {
+        let t = self.a & v;
+        let carry_in = self.flag(FLAG_C) as u8;
+        let mut result = (t >> 1) | (carry_in << 7);
+        self.set_nz(result);
+        if !self.flag(FLAG_D) {
+            self.set_flag(FLAG_C, (result & 0x40) != 0);
+            self.set_flag(FLAG_V, ((result >> 6) ^ (result >> 5)) & 1 != 0);
+            self.a = result;
+            return;
+        }
+
+        // Decimal mode applies BCD fix-ups to each nibble of the rotated value.
+        self.set_flag(FLAG_V, ((t ^ result) & 0x40) != 0);
+        let (hi, lo) = (t >> 4, t & 0x0f);
+        if lo + (lo & 1) > 5 {
+            result = (result & 0xf0) | (result.wrapping_add(6) & 0x0f);
+        }
+        let carry = hi + (hi & 1) > 5;
+        self.set_flag(FLAG_C, carry);
+        if carry {
+            result = result.wrapping_add(0x60);
+        }
+        self.a = result;
+    }
+
+    fn is_cmos(&self) -> bool {
+        self.variant == CpuVariant::Cmos65C02
+    }
+
+    // ADC with the variant's decimal behavior; returns the 65C02's extra decimal cycle.
+    fn add(&mut self, v: u8) -> u32 {
+        if !self.flag(FLAG_D) {
+            self.adc_binary(v);
+            0
+        } else if self.is_cmos() {
+            self.adc_decimal(v);
+            1
+        } else {
+            self.adc_decimal_nmos(v);
+            0
+        }
+    }
+
+    fn subtract(&mut self, v: u8) -> u32 {
+        if !self.flag(FLAG_D) {
+            self.adc_binary(!v);
+            0
+        } else if self.is_cmos() {
+            self.sbc_decimal(v);
+            1
+        } else {
+            self.sbc_decimal_nmos(v);
+            0
+        }
+    }
+
+    fn modify(&mut self, op: Op, v: u8) -> u8 {
+        let result = match op {
+            Op::Asl => {
+                self.set_flag(FLAG_C, (v & 0x80) != 0);
+                v << 1
+            }
+            Op::Lsr => {
+                self.set_flag(FLAG_C, (v & 0x01) != 0);
+                v >> 1
+            }
+            Op::Rol => {
+                let carry_in = self.flag(FLAG_C) as u8;
+                self.set_flag(FLAG_C, (v & 0x80) != 0);
+                (v << 1) | carry_in
+            }
+            Op::Ror => {
+                let carry_in = (self.flag(FLAG_C) as u8) << 7;
+                self.set_flag(FLAG_C, (v & 0x01) != 0);
+                (v >> 1) | carry_in
+            }
+            Op::Inc => v.wrapping_add(1),
+            Op::Dec => v.wrapping_sub(1),
+            _ => unreachable!("{:?} is not a read-modify-write operation", op),
+        };
+        self.set_nz(result);
+        result
+    }
+
+    fn branch<B: Bus + ?Sized>(&mut self, bus: &mut B, taken: bool) -> u32 {
+        let offset = self.fetch(bus) as i8;
+        if !taken {
+            return 0;
+        }
+        let target = self.pc.wrapping_add(offset as u16);
+        let crossed = (target & 0xff00) != (self.pc & 0xff00);
+        self.pc = target;
+        if crossed { 2 } else { 1 }
+    }
+
+    fn compare(&mut self, reg: u8, v: u8) {
+        self.set_flag(FLAG_C, reg >= v);
+        self.set_nz(reg.wrapping_sub(v));
+    }
+
+    fn adc_binary(&mut self, v: u8) {
+        let sum = self.a as u16 + v as u16 + self.flag(FLAG_C) as u16;
+        let result = sum as u8;
+        self.set_flag(FLAG_C, sum > 0xff);
+        self.set_flag(FLAG_V, ((self.a ^ result) & (v ^ result) & 0x80) != 0);
+        self.a = result;
+        self.set_nz(result);
+    }
+
+    fn adc_decimal(&mut self, v: u8) {
+        let a = self.a as i16;
+        let m = v as i16;
+        let carry = self.flag(FLAG_C) as i16;
+
+        let mut lo = (a & 0x0f) + (m & 0x0f) + carry;
+        if lo >= 0x0a {
+            lo = ((lo + 0x06) & 0x0f) + 0x10;
+        }
+
+        let signed = (a & 0xf0) as u8 as i8 as i16 + (m & 0xf0) as u8 as i8 as i16 + lo;
+        self.set_flag(FLAG_V, !(-128..=127).contains(&signed));
+
+        let mut sum = (a & 0xf0) + (m & 0xf0) + lo;
+        if sum >= 0xa0 {
+            sum += 0x60;
+        }
+        self.set_flag(FLAG_C, sum >= 0x100);
+        self.a = sum as u8;
+        self.set_nz(self.a);
+    }
+
+    fn sbc_decimal(&mut self, v: u8) {
+        let a = self.a as i16;
+        let m = v as i16;
+        let borrow = 1 - self.flag(FLAG_C) as i16;
+
+        // Carry and overflow follow the binary subtraction.
+        let binary = self.a as u16 + (!v) as u16 + self.flag(FLAG_C) as u16;
+        let binary_result = binary as u8;
+        self.set_flag(FLAG_C, binary > 0xff);
+        self.set_flag(
+            FLAG_V,
+            ((self.a ^ binary_result) & (!v ^ binary_result) & 0x80) != 0,
+        );
+
+        let lo = (a & 0x0f) - (m & 0x0f) - borrow;
+        let mut result = a - m - borrow;
+        if result < 0 {
+            result -= 0x60;
+        }
+        if lo < 0 {
+            result -= 0x06;
+        }
+        self.a = result as u8;
+        self.set_nz(self.a);
+    }
+
+    // NMOS decimal ADC: Z comes from the binary sum, N and V from the result
+    // before the high-nibble adjustment.
+    fn adc_decimal_nmos(&mut self, v: u8) {
+        let a = self.a as i16;
+        let m = v as i16;
+        let carry = self.flag(FLAG_C) as i16;
+        let binary = (self.a as u16 + v as u16 + carry as u16) as u8;
+
+        let mut lo = (a & 0x0f) + (m & 0x0f) + carry;
+        if lo >= 0x0a {
+            lo = ((lo + 0x06) & 0x0f) + 0x10;
+        }
+
+        let signed = (a & 0xf0) as u8 as i8 as i16 + (m & 0xf0) as u8 as i8 as i16 + lo;
+        self.set_flag(FLAG_V, !(-128..=127).contains(&signed));
+
+        let mut sum = (a & 0xf0) + (m & 0xf0) + lo;
+        self.set_flag(FLAG_N, (sum & 0x80) != 0);
+        self.set_flag(FLAG_Z, binary == 0);
+        if sum >= 0xa0 {
+            sum += 0x60;
+        }
+        self.set_flag(FLAG_C, sum >= 0x100);
+        self.a = sum as u8;
+    }
+
+    // NMOS decimal SBC: every flag follows the binary subtraction.
+    fn sbc_decimal_nmos(&mut self, v: u8) {
+        let a = self.a as i16;
+        let m = v as i16;
+        let carry = self.flag(FLAG_C) as i16;
+
+        let mut lo = (a & 0x0f) - (m & 0x0f) + carry - 1;
+        if lo < 0 {
+            lo = ((lo - 0x06) & 0x0f) - 0x10;
+        }
+        let mut result = (a & 0xf0) - (m & 0xf0) + lo;
+        if result < 0 {
+            result -= 0x60;
+        }
+
+        self.adc_binary(!v);
+        self.a = result as u8;
+    }
+}
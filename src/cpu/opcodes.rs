@@ -0,0 +1,411 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddrMode {
+    Implied,
+    Accumulator,
+    Immediate,
+    ZeroPage,
+    ZeroPageX,
+    ZeroPageY,
+    Absolute,
+    AbsoluteX,
+    AbsoluteY,
+    Indirect,
+    IndexedIndirect,
+    IndirectIndexed,
+    ZeroPageIndirect,
+    AbsoluteIndexedIndirect,
+    Relative,
+}
+
+impl AddrMode {
+    pub fn operand_len(self) -> u16 {
+        match self {
+            AddrMode::Implied | AddrMode::Accumulator => 0,
+            AddrMode::Immediate
+            | AddrMode::ZeroPage
+            | AddrMode::ZeroPageX
+            | AddrMode::ZeroPageY
+            | AddrMode::IndexedIndirect
+            | AddrMode::IndirectIndexed
+            | AddrMode::ZeroPageIndirect
+            | AddrMode::Relative => 1,
+            AddrMode::Absolute
+            | AddrMode::AbsoluteX
+            | AddrMode::AbsoluteY
+            | AddrMode::Indirect
+            | AddrMode::AbsoluteIndexedIndirect => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Adc,
+    And,
+    Asl,
+    Bcc,
+    Bcs,
+    Beq,
+    Bit,
+    Bmi,
+    Bne,
+    Bpl,
+    Bra,
+    Brk,
+    Bvc,
+    Bvs,
+    Clc,
+    Cld,
+    Cli,
+    Clv,
+    Cmp,
+    Cpx,
+    Cpy,
+    Dec,
+    Dex,
+    Dey,
+    Eor,
+    Inc,
+    Inx,
+    Iny,
+    Jmp,
+    Jsr,
+    Lda,
+    Ldx,
+    Ldy,
+    Lsr,
+    Nop,
+    Ora,
+    Pha,
+    Php,
+    Phx,
+    Phy,
+    Pla,
+    Plp,
+    Plx,
+    Ply,
+    Rol,
+    Ror,
+    Rti,
+    Rts,
+    Sbc,
+    Sec,
+    Sed,
+    Sei,
+    Sta,
+    Stx,
+    Sty,
+    Stz,
+    Tax,
+    Tay,
+    Trb,
+    Tsb,
+    Tsx,
+    Txa,
+    Txs,
+    Tya,
+    // NMOS undocumented opcodes.
+    Alr,
+    Anc,
+    Ane,
+    Arr,
+    Dcp,
+    Isc,
+    Jam,
+    Las,
+    Lax,
+    Lxa,
+    Rla,
+    Rra,
+    Sax,
+    Sbx,
+    Sha,
+    Shx,
+    Shy,
+    Slo,
+    Sre,
+    Tas,
+}
+
+impl Op {
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Op::Adc => "ADC",
+            Op::And => "AND",
+            Op::Asl => "ASL",
+            Op::Bcc => "BCC",
+            Op::Bcs => "BCS",
+            Op::Beq => "BEQ",
+            Op::Bit => "BIT",
+            Op::Bmi => "BMI",
+            Op::Bne => "BNE",
+            Op::Bpl => "BPL",
+            Op::Bra => "BRA",
+            Op::Brk => "BRK",
+            Op::Bvc => "BVC",
+            Op::Bvs => "BVS",
+            Op::Clc => "CLC",
+            Op::Cld => "CLD",
+            Op::Cli => "CLI",
+            Op::Clv => "CLV",
+            Op::Cmp => "CMP",
+            Op::Cpx => "CPX",
+            Op::Cpy => "CPY",
+            Op::Dec => "DEC",
+            Op::Dex => "DEX",
+            Op::Dey => "DEY",
+            Op::Eor => "EOR",
+            Op::Inc => "INC",
+            Op::Inx => "INX",
+            Op::Iny => "INY",
+            Op::Jmp => "JMP",
+            Op::Jsr => "JSR",
+            Op::Lda => "LDA",
+            Op::Ldx => "LDX",
+            Op::Ldy => "LDY",
+            Op::Lsr => "LSR",
+            Op::Nop => "NOP",
+            Op::Ora => "ORA",
+            Op::Pha => "PHA",
+            Op::Php => "PHP",
+            Op::Phx => "PHX",
+            Op::Phy => "PHY",
+            Op::Pla => "PLA",
+            Op::Plp => "PLP",
+            Op::Plx => "PLX",
+            Op::Ply => "PLY",
+            Op::Rol => "ROL",
+            Op::Ror => "ROR",
+            Op::Rti => "RTI",
+            Op::Rts => "RTS",
+            Op::Sbc => "SBC",
+            Op::Sec => "SEC",
+            Op::Sed => "SED",
+            Op::Sei => "SEI",
+            Op::Sta => "STA",
+            Op::Stx => "STX",
+            Op::Sty => "STY",
+            Op::Stz => "STZ",
+            Op::Tax => "TAX",
+            Op::Tay => "TAY",
+            Op::Trb => "TRB",
+            Op::Tsb => "TSB",
+            Op::Tsx => "TSX",
+            Op::Txa => "TXA",
+            Op::Txs => "TXS",
+            Op::Tya => "TYA",
+            Op::Alr => "ALR",
+            Op::Anc => "ANC",
+            Op::Ane => "ANE",
+            Op::Arr => "ARR",
+            Op::Dcp => "DCP",
+            Op::Isc => "ISC",
+            Op::Jam => "JAM",
+            Op::Las => "LAS",
+            Op::Lax => "LAX",
+            Op::Lxa => "LXA",
+            Op::Rla => "RLA",
+            Op::Rra => "RRA",
+            Op::Sax => "SAX",
+            Op::Sbx => "SBX",
+            Op::Sha => "SHA",
+            Op::Shx => "SHX",
+            Op::Shy => "SHY",
+            Op::Slo => "SLO",
+            Op::Sre => "SRE",
+            Op::Tas => "TAS",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Opcode {
+    pub op: Op,
+    pub mode: AddrMode,
+    pub cycles: u8,
+}
+
+impl Opcode {
+    pub fn size(&self) -> u16 {
+        1 + self.mode.operand_len()
+    }
+}
+
+const fn oc(op: Op, mode: AddrMode, cycles: u8) -> Opcode {
+    Opcode { op, mode, cycles }
+}
+
+use AddrMode::*;
+use Op::*;
+
+// Undefined opcodes on the IIe's 65C02 (no Rockwell bit instructions) are NOPs
+// with fixed lengths and timings.
+#[rustfmt::skip]
+pub static CMOS_OPCODES: [Opcode; 256] = [
+    // 0x00
+    oc(Brk, Implied, 7), oc(Ora, IndexedIndirect, 6), oc(Nop, Immediate, 2), oc(Nop, Implied, 1),
+    oc(Tsb, ZeroPage, 5), oc(Ora, ZeroPage, 3), oc(Asl, ZeroPage, 5), oc(Nop, Implied, 1),
+    oc(Php, Implied, 3), oc(Ora, Immediate, 2), oc(Asl, Accumulator, 2), oc(Nop, Implied, 1),
+    oc(Tsb, Absolute, 6), oc(Ora, Absolute, 4), oc(Asl, Absolute, 6), oc(Nop, Implied, 1),
+    // 0x10
+    oc(Bpl, Relative, 2), oc(Ora, IndirectIndexed, 5), oc(Ora, ZeroPageIndirect, 5), oc(Nop, Implied, 1),
+    oc(Trb, ZeroPage, 5), oc(Ora, ZeroPageX, 4), oc(Asl, ZeroPageX, 6), oc(Nop, Implied, 1),
+    oc(Clc, Implied, 2), oc(Ora, AbsoluteY, 4), oc(Inc, Accumulator, 2), oc(Nop, Implied, 1),
+    oc(Trb, Absolute, 6), oc(Ora, AbsoluteX, 4), oc(Asl, AbsoluteX, 6), oc(Nop, Implied, 1),
+    // 0x20
+    oc(Jsr, Absolute, 6), oc(And, IndexedIndirect, 6), oc(Nop, Immediate, 2), oc(Nop, Implied, 1),
+    oc(Bit, ZeroPage, 3), oc(And, ZeroPage, 3), oc(Rol, ZeroPage, 5), oc(Nop, Implied, 1),
+    oc(Plp, Implied, 4), oc(And, Immediate, 2), oc(Rol, Accumulator, 2), oc(Nop, Implied, 1),
+    oc(Bit, Absolute, 4), oc(And, Absolute, 4), oc(Rol, Absolute, 6), oc(Nop, Implied, 1),
+    // 0x30
+    oc(Bmi, Relative, 2), oc(And, IndirectIndexed, 5), oc(And, ZeroPageIndirect, 5), oc(Nop, Implied, 1),
+    oc(Bit, ZeroPageX, 4), oc(And, ZeroPageX, 4), oc(Rol, ZeroPageX, 6), oc(Nop, Implied, 1),
+    oc(Sec, Implied, 2), oc(And, AbsoluteY, 4), oc(Dec, Accumulator, 2), oc(Nop, Implied, 1),
+    oc(Bit, AbsoluteX, 4), oc(And, AbsoluteX, 4), oc(Rol, AbsoluteX, 6), oc(Nop, Implied, 1),
+    // 0x40
+    oc(Rti, Implied, 6), oc(Eor, IndexedIndirect, 6), oc(Nop, Immediate, 2), oc(Nop, Implied, 1),
+    oc(Nop, ZeroPage, 3), oc(Eor, ZeroPage, 3), oc(Lsr, ZeroPage, 5), oc(Nop, Implied, 1),
+    oc(Pha, Implied, 3), oc(Eor, Immediate, 2), oc(Lsr, Accumulator, 2), oc(Nop, Implied, 1),
+    oc(Jmp, Absolute, 3), oc(Eor, Absolute, 4), oc(Lsr, Absolute, 6), oc(Nop, Implied, 1),
+    // 0x50
+    oc(Bvc, Relative, 2), oc(Eor, IndirectIndexed, 5), oc(Eor, ZeroPageIndirect, 5), oc(Nop, Implied, 1),
+    oc(Nop, ZeroPageX, 4), oc(Eor, ZeroPageX, 4), oc(Lsr, ZeroPageX, 6), oc(Nop, Implied, 1),
+    oc(Cli, Implied, 2), oc(Eor, AbsoluteY, 4), oc(Phy, Implied, 3), oc(Nop, Implied, 1),
+    oc(Nop, Absolute, 8), oc(Eor, AbsoluteX, 4), oc(Lsr, AbsoluteX, 6), oc(Nop, Implied, 1),
+    // 0x60
+    oc(Rts, Implied, 6), oc(Adc, IndexedIndirect, 6), oc(Nop, Immediate, 2), oc(Nop, Implied, 1),
+    oc(Stz, ZeroPage, 3), oc(Adc, ZeroPage, 3), oc(Ror, ZeroPage, 5), oc(Nop, Implied, 1),
+    oc(Pla, Implied, 4), oc(Adc, Immediate, 2), oc(Ror, Accumulator, 2), oc(Nop, Implied, 1),
+    oc(Jmp, Indirect, 6), oc(Adc, Absolute, 4), oc(Ror, Absolute, 6), oc(Nop, Implied, 1),
+    // 0x70
+    oc(Bvs, Relative, 2), oc(Adc, IndirectIndexed, 5), oc(Adc, ZeroPageIndirect, 5), oc(Nop, Implied, 1),
+    oc(Stz, ZeroPageX, 4), oc(Adc, ZeroPageX, 4), oc(Ror, ZeroPageX, 6), oc(Nop, Implied, 1),
+    oc(Sei, Implied, 2), oc(Adc, AbsoluteY, 4), oc(Ply, Implied, 4), oc(Nop, Implied, 1),
+    oc(Jmp, AbsoluteIndexedIndirect, 6), oc(Adc, AbsoluteX, 4), oc(Ror, AbsoluteX, 6), oc(Nop, Implied, 1),
+    // 0x80
+    oc(Bra, Relative, 3), oc(Sta, IndexedIndirect, 6), oc(Nop, Immediate, 2), oc(Nop, Implied, 1),
+    oc(Sty, ZeroPage, 3), oc(Sta, ZeroPage, 3), oc(Stx, ZeroPage, 3), oc(Nop, Implied, 1),
+    oc(Dey, Implied, 2), oc(Bit, Immediate, 2), oc(Txa, Implied, 2), oc(Nop, Implied, 1),
+    oc(Sty, Absolute, 4), oc(Sta, Absolute, 4), oc(Stx, Absolute, 4), oc(Nop, Implied, 1),
+    // 0x90
+    oc(Bcc, Relative, 2), oc(Sta, IndirectIndexed, 6), oc(Sta, ZeroPageIndirect, 5), oc(Nop, Implied, 1),
+    oc(Sty, ZeroPageX, 4), oc(Sta, ZeroPageX, 4), oc(Stx, ZeroPageY, 4), oc(Nop, Implied, 1),
+    oc(Tya, Implied, 2), oc(Sta, AbsoluteY, 5), oc(Txs, Implied, 2), oc(Nop, Implied, 1),
+    oc(Stz, Absolute, 4), oc(Sta, AbsoluteX, 5), oc(Stz, AbsoluteX, 5), oc(Nop, Implied, 1),
+    // 0xA0
+    oc(Ldy, Immediate, 2), oc(Lda, IndexedIndirect, 6), oc(Ldx, Immediate, 2), oc(Nop, Implied, 1),
+    oc(Ldy, ZeroPage, 3), oc(Lda, ZeroPage, 3), oc(Ldx, ZeroPage, 3), oc(Nop, Implied, 1),
+    oc(Tay, Implied, 2), oc(Lda, Immediate, 2), oc(Tax, Implied, 2), oc(Nop, Implied, 1),
+    oc(Ldy, Absolute, 4), oc(Lda, Absolute, 4), oc(Ldx, Absolute, 4), oc(Nop, Implied, 1),
+    // 0xB0
+    oc(Bcs, Relative, 2), oc(Lda, IndirectIndexed, 5), oc(Lda, ZeroPageIndirect, 5), oc(Nop, Implied, 1),
+    oc(Ldy, ZeroPageX, 4), oc(Lda, ZeroPageX, 4), oc(Ldx, ZeroPageY, 4), oc(Nop, Implied, 1),
+    oc(Clv, Implied, 2), oc(Lda, AbsoluteY, 4), oc(Tsx, Implied, 2), oc(Nop, Implied, 1),
+    oc(Ldy, AbsoluteX, 4), oc(Lda, AbsoluteX, 4), oc(Ldx, AbsoluteY, 4), oc(Nop, Implied, 1),
+    // 0xC0
+    oc(Cpy, Immediate, 2), oc(Cmp, IndexedIndirect, 6), oc(Nop, Immediate, 2), oc(Nop, Implied, 1),
+    oc(Cpy, ZeroPage, 3), oc(Cmp, ZeroPage, 3), oc(Dec, ZeroPage, 5), oc(Nop, Implied, 1),
+    oc(Iny, Implied, 2), oc(Cmp, Immediate, 2), oc(Dex, Implied, 2), oc(Nop, Implied, 1),
+    oc(Cpy, Absolute, 4), oc(Cmp, Absolute, 4), oc(Dec, Absolute, 6), oc(Nop, Implied, 1),
+    // 0xD0
+    oc(Bne, Relative, 2), oc(Cmp, IndirectIndexed, 5), oc(Cmp, ZeroPageIndirect, 5), oc(Nop, Implied, 1),
+    oc(Nop, ZeroPageX, 4), oc(Cmp, ZeroPageX, 4), oc(Dec, ZeroPageX, 6), oc(Nop, Implied, 1),
+    oc(Cld, Implied, 2), oc(Cmp, AbsoluteY, 4), oc(Phx, Implied, 3), oc(Nop, Implied, 1),
+    oc(Nop, Absolute, 4), oc(Cmp, AbsoluteX, 4), oc(Dec, AbsoluteX, 7), oc(Nop, Implied, 1),
+    // 0xE0
+    oc(Cpx, Immediate, 2), oc(Sbc, IndexedIndirect, 6), oc(Nop, Immediate, 2), oc(Nop, Implied, 1),
+    oc(Cpx, ZeroPage, 3), oc(Sbc, ZeroPage, 3), oc(Inc, ZeroPage, 5), oc(Nop, Implied, 1),
+    oc(Inx, Implied, 2), oc(Sbc, Immediate, 2), oc(Nop, Implied, 2), oc(Nop, Implied, 1),
+    oc(Cpx, Absolute, 4), oc(Sbc, Absolute, 4), oc(Inc, Absolute, 6), oc(Nop, Implied, 1),
+    // 0xF0
+    oc(Beq, Relative, 2), oc(Sbc, IndirectIndexed, 5), oc(Sbc, ZeroPageIndirect, 5), oc(Nop, Implied, 1),
+    oc(Nop, ZeroPageX, 4), oc(Sbc, ZeroPageX, 4), oc(Inc, ZeroPageX, 6), oc(Nop, Implied, 1),
+    oc(Sed, Implied, 2), oc(Sbc, AbsoluteY, 4), oc(Plx, Implied, 4), oc(Nop, Implied, 1),
+    oc(Nop, Absolute, 4), oc(Sbc, AbsoluteX, 4), oc(Inc, AbsoluteX, 7), oc(Nop, Implied, 1),
+];
+
+// NMOS 6502 as fitted to the Apple II/II+: the documented set plus the stable
+// and unstable undocumented opcodes; the twelve JAM opcodes halt the CPU.
+#[rustfmt::skip]
+pub static NMOS_OPCODES: [Opcode; 256] = [
+    // 0x00
+    oc(Brk, Implied, 7), oc(Ora, IndexedIndirect, 6), oc(Jam, Implied, 2), oc(Slo, IndexedIndirect, 8),
+    oc(Nop, ZeroPage, 3), oc(Ora, ZeroPage, 3), oc(Asl, ZeroPage, 5), oc(Slo, ZeroPage, 5),
+    oc(Php, Implied, 3), oc(Ora, Immediate, 2), oc(Asl, Accumulator, 2), oc(Anc, Immediate, 2),
+    oc(Nop, Absolute, 4), oc(Ora, Absolute, 4), oc(Asl, Absolute, 6), oc(Slo, Absolute, 6),
+    // 0x10
+    oc(Bpl, Relative, 2), oc(Ora, IndirectIndexed, 5), oc(Jam, Implied, 2), oc(Slo, IndirectIndexed, 8),
+    oc(Nop, ZeroPageX, 4), oc(Ora, ZeroPageX, 4), oc(Asl, ZeroPageX, 6), oc(Slo, ZeroPageX, 6),
+    oc(Clc, Implied, 2), oc(Ora, AbsoluteY, 4), oc(Nop, Implied, 2), oc(Slo, AbsoluteY, 7),
+    oc(Nop, AbsoluteX, 4), oc(Ora, AbsoluteX, 4), oc(Asl, AbsoluteX, 7), oc(Slo, AbsoluteX, 7),
+    // 0x20
+    oc(Jsr, Absolute, 6), oc(And, IndexedIndirect, 6), oc(Jam, Implied, 2), oc(Rla, IndexedIndirect, 8),
+    oc(Bit, ZeroPage, 3), oc(And, ZeroPage, 3), oc(Rol, ZeroPage, 5), oc(Rla, ZeroPage, 5),
+    oc(Plp, Implied, 4), oc(And, Immediate, 2), oc(Rol, Accumulator, 2), oc(Anc, Immediate, 2),
+    oc(Bit, Absolute, 4), oc(And, Absolute, 4), oc(Rol, Absolute, 6), oc(Rla, Absolute, 6),
+    // 0x30
+    oc(Bmi, Relative, 2), oc(And, IndirectIndexed, 5), oc(Jam, Implied, 2), oc(Rla, IndirectIndexed, 8),
+    oc(Nop, ZeroPageX, 4), oc(And, ZeroPageX, 4), oc(Rol, ZeroPageX, 6), oc(Rla, ZeroPageX, 6),
+    oc(Sec, Implied, 2), oc(And, AbsoluteY, 4), oc(Nop, Implied, 2), oc(Rla, AbsoluteY, 7),
+    oc(Nop, AbsoluteX, 4), oc(And, AbsoluteX, 4), oc(Rol, AbsoluteX, 7), oc(Rla, AbsoluteX, 7),
+    // 0x40
+    oc(Rti, Implied, 6), oc(Eor, IndexedIndirect, 6), oc(Jam, Implied, 2), oc(Sre, IndexedIndirect, 8),
+    oc(Nop, ZeroPage, 3), oc(Eor, ZeroPage, 3), oc(Lsr, ZeroPage, 5), oc(Sre, ZeroPage, 5),
+    oc(Pha, Implied, 3), oc(Eor, Immediate, 2), oc(Lsr, Accumulator, 2), oc(Alr, Immediate, 2),
+    oc(Jmp, Absolute, 3), oc(Eor, Absolute, 4), oc(Lsr, Absolute, 6), oc(Sre, Absolute, 6),
+    // 0x50
+    oc(Bvc, Relative, 2), oc(Eor, IndirectIndexed, 5), oc(Jam, Implied, 2), oc(Sre, IndirectIndexed, 8),
+    oc(Nop, ZeroPageX, 4), oc(Eor, ZeroPageX, 4), oc(Lsr, ZeroPageX, 6), oc(Sre, ZeroPageX, 6),
+    oc(Cli, Implied, 2), oc(Eor, AbsoluteY, 4), oc(Nop, Implied, 2), oc(Sre, AbsoluteY, 7),
+    oc(Nop, AbsoluteX, 4), oc(Eor, AbsoluteX, 4), oc(Lsr, AbsoluteX, 7), oc(Sre, AbsoluteX, 7),
+    // 0x60
+    oc(Rts, Implied, 6), oc(Adc, IndexedIndirect, 6), oc(Jam, Implied, 2), oc(Rra, IndexedIndirect, 8),
+    oc(Nop, ZeroPage, 3), oc(Adc, ZeroPage, 3), oc(Ror, ZeroPage, 5), oc(Rra, ZeroPage, 5),
+    oc(Pla, Implied, 4), oc(Adc, Immediate, 2), oc(Ror, Accumulator, 2), oc(Arr, Immediate, 2),
+    oc(Jmp, Indirect, 5), oc(Adc, Absolute, 4), oc(Ror, Absolute, 6), oc(Rra, Absolute, 6),
+    // 0x70
+    oc(Bvs, Relative, 2), oc(Adc, IndirectIndexed, 5), oc(Jam, Implied, 2), oc(Rra, IndirectIndexed, 8),
+    oc(Nop, ZeroPageX, 4), oc(Adc, ZeroPageX, 4), oc(Ror, ZeroPageX, 6), oc(Rra, ZeroPageX, 6),
+    oc(Sei, Implied, 2), oc(Adc, AbsoluteY, 4), oc(Nop, Implied, 2), oc(Rra, AbsoluteY, 7),
+    oc(Nop, AbsoluteX, 4), oc(Adc, AbsoluteX, 4), oc(Ror, AbsoluteX, 7), oc(Rra, AbsoluteX, 7),
+    // 0x80
+    oc(Nop, Immediate, 2), oc(Sta, IndexedIndirect, 6), oc(Nop, Immediate, 2), oc(Sax, IndexedIndirect, 6),
+    oc(Sty, ZeroPage, 3), oc(Sta, ZeroPage, 3), oc(Stx, ZeroPage, 3), oc(Sax, ZeroPage, 3),
+    oc(Dey, Implied, 2), oc(Nop, Immediate, 2), oc(Txa, Implied, 2), oc(Ane, Immediate, 2),
+    oc(Sty, Absolute, 4), oc(Sta, Absolute, 4), oc(Stx, Absolute, 4), oc(Sax, Absolute, 4),
+    // 0x90
+    oc(Bcc, Relative, 2), oc(Sta, IndirectIndexed, 6), oc(Jam, Implied, 2), oc(Sha, IndirectIndexed, 6),
+    oc(Sty, ZeroPageX, 4), oc(Sta, ZeroPageX, 4), oc(Stx, ZeroPageY, 4), oc(Sax, ZeroPageY, 4),
+    oc(Tya, Implied, 2), oc(Sta, AbsoluteY, 5), oc(Txs, Implied, 2), oc(Tas, AbsoluteY, 5),
+    oc(Shy, AbsoluteX, 5), oc(Sta, AbsoluteX, 5), oc(Shx, AbsoluteY, 5), oc(Sha, AbsoluteY, 5),
+    // 0xA0
+    oc(Ldy, Immediate, 2), oc(Lda, IndexedIndirect, 6), oc(Ldx, Immediate, 2), oc(Lax, IndexedIndirect, 6),
+    oc(Ldy, ZeroPage, 3), oc(Lda, ZeroPage, 3), oc(Ldx, ZeroPage, 3), oc(Lax, ZeroPage, 3),
+    oc(Tay, Implied, 2), oc(Lda, Immediate, 2), oc(Tax, Implied, 2), oc(Lxa, Immediate, 2),
+    oc(Ldy, Absolute, 4), oc(Lda, Absolute, 4), oc(Ldx, Absolute, 4), oc(Lax, Absolute, 4),
+    // 0xB0
+    oc(Bcs, Relative, 2), oc(Lda, IndirectIndexed, 5), oc(Jam, Implied, 2), oc(Lax, IndirectIndexed, 5),
+    oc(Ldy, ZeroPageX, 4), oc(Lda, ZeroPageX, 4), oc(Ldx, ZeroPageY, 4), oc(Lax, ZeroPageY, 4),
+    oc(Clv, Implied, 2), oc(Lda, AbsoluteY, 4), oc(Tsx, Implied, 2), oc(Las, AbsoluteY, 4),
+    oc(Ldy, AbsoluteX, 4), oc(Lda, AbsoluteX, 4), oc(Ldx, AbsoluteY, 4), oc(Lax, AbsoluteY, 4),
+    // 0xC0
+    oc(Cpy, Immediate, 2), oc(Cmp, IndexedIndirect, 6), oc(Nop, Immediate, 2), oc(Dcp, IndexedIndirect, 8),
+    oc(Cpy, ZeroPage, 3), oc(Cmp, ZeroPage, 3), oc(Dec, ZeroPage, 5), oc(Dcp, ZeroPage, 5),
+    oc(Iny, Implied, 2), oc(Cmp, Immediate, 2), oc(Dex, Implied, 2), oc(Sbx, Immediate, 2),
+    oc(Cpy, Absolute, 4), oc(Cmp, Absolute, 4), oc(Dec, Absolute, 6), oc(Dcp, Absolute, 6),
+    // 0xD0
+    oc(Bne, Relative, 2), oc(Cmp, IndirectIndexed, 5), oc(Jam, Implied, 2), oc(Dcp, IndirectIndexed, 8),
+    oc(Nop, ZeroPageX, 4), oc(Cmp, ZeroPageX, 4), oc(Dec, ZeroPageX, 6), oc(Dcp, ZeroPageX, 6),
+    oc(Cld, Implied, 2), oc(Cmp, AbsoluteY, 4), oc(Nop, Implied, 2), oc(Dcp, AbsoluteY, 7),
+    oc(Nop, AbsoluteX, 4), oc(Cmp, AbsoluteX, 4), oc(Dec, AbsoluteX, 7), oc(Dcp, AbsoluteX, 7),
+    // 0xE0
+    oc(Cpx, Immediate, 2), oc(Sbc, IndexedIndirect, 6), oc(Nop, Immediate, 2), oc(Isc, IndexedIndirect, 8),
+    oc(Cpx, ZeroPage, 3), oc(Sbc, ZeroPage, 3), oc(Inc, ZeroPage, 5), oc(Isc, ZeroPage, 5),
+    oc(Inx, Implied, 2), oc(Sbc, Immediate, 2), oc(Nop, Implied, 2), oc(Sbc, Immediate, 2),
+    oc(Cpx, Absolute, 4), oc(Sbc, Absolute, 4), oc(Inc, Absolute, 6), oc(Isc, Absolute, 6),
+    // 0xF0
+    oc(Beq, Relative, 2), oc(Sbc, IndirectIndexed, 5), oc(Jam, Implied, 2), oc(Isc, IndirectIndexed, 8),
+    oc(Nop, ZeroPageX, 4), oc(Sbc, ZeroPageX, 4), oc(Inc, ZeroPageX, 6), oc(Isc, ZeroPageX, 6),
+    oc(Sed, Implied, 2), oc(Sbc, AbsoluteY, 4), oc(Nop, Implied, 2), oc(Isc, AbsoluteY, 7),
+    oc(Nop, AbsoluteX, 4), oc(Sbc, AbsoluteX, 4), oc(Inc, AbsoluteX, 7), oc(Isc, AbsoluteX, 7),
+];
@@ -1,11 +1,19 @@
+pub mod bus;
 pub mod capture;
+pub mod checksum;
 pub mod config;
+pub mod cpu;
 pub mod lab;
 pub mod machines;
+pub mod monitor;
 pub mod postfx;
 pub mod rng;
+pub mod rom;
+pub mod runner;
+pub mod scheduler;
 #[cfg(feature = "sdl3")]
 pub mod sdl_display_core;
 pub mod screen_buffer;
 pub mod timing;
+pub mod trace;
 pub mod video;
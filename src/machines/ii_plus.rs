@@ -0,0 +1,17 @@
+use super::Machine;
+use super::iie::memory::IieMemory;
+use super::ram_init::RamInit;
+use crate::cpu::{Cpu, CpuVariant};
+use crate::scheduler::{MasterClock, Scheduler};
+
+pub fn apple_ii_plus() -> Machine {
+    Machine {
+        name: "Apple II+",
+        description: "NMOS 6502 with undocumented opcodes, 48K plus a 16K language card.",
+        cpu: Cpu::new().with_variant(CpuVariant::Nmos6502),
+        memory: IieMemory::ii_plus(),
+        ram_init: RamInit::default(),
+        clock: MasterClock::default(),
+        events: Scheduler::new(),
+    }
+}
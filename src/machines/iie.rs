@@ -1,8 +0,0 @@
-use super::Machine;
-
-pub fn apple_iie() -> Machine {
-    Machine {
-        name: "Apple IIe",
-        description: "First machine in EchoLab. Focus: accurate 65C02 core and timing.",
-    }
-}
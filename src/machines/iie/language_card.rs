@@ -0,0 +1,41 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageCard {
+    pub read_ram: bool,
+    pub write_enabled: bool,
+    pub bank2: bool,
+    prewrite: bool,
+}
+
+impl Default for LanguageCard {
+    fn default() -> Self {
+        // Power-on state: ROM readable, RAM write-enabled into bank 2.
+        Self {
+            read_ram: false,
+            write_enabled: true,
+            bank2: true,
+            prewrite: false,
+        }
+    }
+}
+
+impl LanguageCard {
+    // $C080-$C08F. Bit 3 picks bank 1, bits 0-1 pick the read source and write
+    // enable; enabling writes takes two consecutive odd-address reads.
+    pub fn access(&mut self, addr: u16, write: bool) {
+        let low = addr & 0x0f;
+        self.bank2 = (low & 0x08) == 0;
+        self.read_ram = matches!(low & 0x03, 0x00 | 0x03);
+
+        if (low & 0x01) == 0 {
+            self.write_enabled = false;
+            self.prewrite = false;
+        } else if write {
+            self.prewrite = false;
+        } else {
+            if self.prewrite {
+                self.write_enabled = true;
+            }
+            self.prewrite = true;
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use super::language_card::LanguageCard;
+use super::mmu::{Bank, Mmu};
+use crate::bus::Bus;
+use crate::machines::ram_init::RamInit;
+use crate::rom::SystemRom;
+use crate::video::VideoSwitches;
+use crate::video::log::{VideoEvent, VideoLog, is_display_addr};
+use crate::video::scanner::BeamPosition;
+
+pub const ROM_BASE: u16 = 0xc000;
+pub const ROM_SIZE: usize = 0x4000;
+pub const LANGUAGE_CARD_BANK_SIZE: usize = 0x1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemoryModel {
+    #[default]
+    Iie,
+    // 48K Apple II+ with a 16K language card in slot 0: no auxiliary memory,
+    // no MMU/IOU switches or status reads, no internal $C100-$CFFF firmware
+    // and an uppercase-only keyboard.
+    IiPlus,
+}
+
+pub struct IieMemory {
+    model: MemoryModel,
+    main: Box<[u8; 65536]>,
+    aux: Box<[u8; 65536]>,
+    // Language card bank 1 at $D000; bank 2 lives in the 64K arrays themselves.
+    main_bank1: Box<[u8; LANGUAGE_CARD_BANK_SIZE]>,
+    aux_bank1: Box<[u8; LANGUAGE_CARD_BANK_SIZE]>,
+    rom: Box<[u8; ROM_SIZE]>,
+    mmu: Mmu,
+    language_card: LanguageCard,
+    intc8rom: bool,
+    keyboard_latch: u8,
+    // Cycle of the latest bus access; `peek` samples the floating bus here.
+    cycle: u64,
+    video_log: VideoLog,
+}
+
+impl Default for IieMemory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IieMemory {
+    pub fn new() -> Self {
+        Self {
+            model: MemoryModel::Iie,
+            main: Box::new([0; 65536]),
+            aux: Box::new([0; 65536]),
+            main_bank1: Box::new([0; LANGUAGE_CARD_BANK_SIZE]),
+            aux_bank1: Box::new([0; LANGUAGE_CARD_BANK_SIZE]),
+            rom: Box::new([0; ROM_SIZE]),
+            mmu: Mmu::default(),
+            language_card: LanguageCard::default(),
+            intc8rom: false,
+            keyboard_latch: 0,
+            cycle: 0,
+            video_log: VideoLog::default(),
+        }
+    }
+
+    pub fn ii_plus() -> Self {
+        Self {
+            model: MemoryModel::IiPlus,
+            ..Self::new()
+        }
+    }
+
+    pub fn model(&self) -> MemoryModel {
+        self.model
+    }
+
+    pub fn initialize_ram(&mut self, init: RamInit) {
+        init.fill(&mut [
+            &mut self.main[..],
+            &mut self.aux[..],
+            &mut self.main_bank1[..],
+            &mut self.aux_bank1[..],
+        ]);
+    }
+
+    pub fn power_on(&mut self) {
+        self.mmu = Mmu::default();
+        self.language_card = LanguageCard::default();
+        self.intc8rom = false;
+        self.keyboard_latch = 0;
+        self.cycle = 0;
+        self.video_log.clear();
+    }
+
+    // Ctrl-Reset: soft switches and the language card return to their power-on
+    // state while RAM contents are preserved.
+    pub fn reset(&mut self) {
+        let switches = self.mmu.video_switches();
+        self.mmu.reset();
+        self.log_switches(switches);
+        self.language_card = LanguageCard::default();
+        self.intc8rom = false;
+    }
+
+    pub fn mmu(&self) -> &Mmu {
+        &self.mmu
+    }
+
+    pub fn mmu_mut(&mut self) -> &mut Mmu {
+        &mut self.mmu
+    }
+
+    pub fn language_card(&self) -> &LanguageCard {
+        &self.language_card
+    }
+
+    pub fn language_card_bank1(&self, bank: Bank) -> &[u8; LANGUAGE_CARD_BANK_SIZE] {
+        match bank {
+            Bank::Main => &self.main_bank1,
+            Bank::Aux => &self.aux_bank1,
+        }
+    }
+
+    pub fn main(&self) -> &[u8; 65536] {
+        &self.main
+    }
+
+    pub fn main_mut(&mut self) -> &mut [u8; 65536] {
+        &mut self.main
+    }
+
+    pub fn aux(&self) -> &[u8; 65536] {
+        &self.aux
+    }
+
+    pub fn aux_mut(&mut self) -> &mut [u8; 65536] {
+        &mut self.aux
+    }
+
+    pub fn load_rom(&mut self, rom: &SystemRom) {
+        let offset = (rom.base() - ROM_BASE) as usize;
+        self.rom[offset..offset + rom.bytes().len()].copy_from_slice(rom.bytes());
+    }
+
+    pub fn press_key(&mut self, ascii: u8) {
+        let ascii = match self.model {
+            MemoryModel::Iie => ascii,
+            MemoryModel::IiPlus => ascii.to_ascii_uppercase(),
+        };
+        self.keyboard_latch = ascii | 0x80;
+    }
+
+    pub fn keyboard_latch(&self) -> u8 {
+        self.keyboard_latch
+    }
+
+    pub fn beam_position(&self) -> BeamPosition {
+        BeamPosition::at(self.cycle)
+    }
+
+    // What an unconnected address returns: the byte the video scanner fetched
+    // from main RAM on the previous half of the cycle.
+    pub fn floating_bus(&self) -> u8 {
+        let position = self.beam_position();
+        let switches = self.mmu.video_switches();
+        let addr = match self.model {
+            MemoryModel::Iie => position.scanner_address(&switches),
+            MemoryModel::IiPlus => position.ii_plus_scanner_address(&switches),
+        };
+        self.main[addr as usize]
+    }
+
+    fn bank(&self, bank: Bank) -> &[u8; 65536] {
+        match bank {
+            Bank::Main => &self.main,
+            Bank::Aux => &self.aux,
+        }
+    }
+
+    fn bank_mut(&mut self, bank: Bank) -> &mut [u8; 65536] {
+        match bank {
+            Bank::Main => &mut self.main,
+            Bank::Aux => &mut self.aux,
+        }
+    }
+
+    fn peek_high(&self, addr: u16) -> u8 {
+        if !self.language_card.read_ram {
+            return self.rom[(addr - ROM_BASE) as usize];
+        }
+        let bank = self.mmu.high_bank();
+        if addr < 0xe000 && !self.language_card.bank2 {
+            self.language_card_bank1(bank)[(addr - 0xd000) as usize]
+        } else {
+            self.bank(bank)[addr as usize]
+        }
+    }
+
+    fn write_high(&mut self, addr: u16, value: u8) {
+        if !self.language_card.write_enabled {
+            return;
+        }
+        let bank = self.mmu.high_bank();
+        if addr < 0xe000 && !self.language_card.bank2 {
+            let bank1 = match bank {
+                Bank::Main => &mut self.main_bank1,
+                Bank::Aux => &mut self.aux_bank1,
+            };
+            bank1[(addr - 0xd000) as usize] = value;
+        } else {
+            self.bank_mut(bank)[addr as usize] = value;
+        }
+    }
+
+    fn internal_rom_selected(&self, addr: u16) -> bool {
+        if self.model == MemoryModel::IiPlus {
+            return false;
+        }
+        match addr {
+            0xc300..=0xc3ff => self.mmu.intcxrom || !self.mmu.slotc3rom,
+            0xc800..=0xcfff => self.mmu.intcxrom || self.intc8rom,
+            _ => self.mmu.intcxrom,
+        }
+    }
+
+    fn peek_firmware(&self, addr: u16) -> u8 {
+        if self.internal_rom_selected(addr) {
+            self.rom[(addr - ROM_BASE) as usize]
+        } else {
+            // No peripheral cards are installed.
+            self.floating_bus()
+        }
+    }
+
+    fn touch_firmware(&mut self, addr: u16) {
+        match addr {
+            0xc300..=0xc3ff if !self.mmu.slotc3rom => self.intc8rom = true,
+            0xcfff => self.intc8rom = false,
+            _ => {}
+        }
+    }
+
+    fn peek_io(&self, addr: u16) -> u8 {
+        let low = addr & 0xff;
+        match low {
+            0x00..=0x0f => self.keyboard_latch,
+            0x10..=0x1f if self.model == MemoryModel::IiPlus => self.floating_bus(),
+            0x10..=0x1f => {
+                let flag = match low {
+                    0x11 => self.language_card.bank2,
+                    0x12 => self.language_card.read_ram,
+                    // RDVBLBAR: bit 7 is low during vertical blanking.
+                    0x19 => !self.beam_position().vbl(),
+                    _ => self.mmu.status(addr).unwrap_or(false),
+                };
+                ((flag as u8) << 7) | (self.keyboard_latch & 0x7f)
+            }
+            // Pushbuttons and cassette input drive bit 7 only (nothing pressed).
+            0x60..=0x6f => self.floating_bus() & 0x7f,
+            // RDDHIRES shares bit 7 with the floating bus.
+            0x7f if self.model == MemoryModel::Iie => {
+                ((self.mmu.dhires as u8) << 7) | (self.floating_bus() & 0x7f)
+            }
+            _ => self.floating_bus(),
+        }
+    }
+
+    fn log_switches(&mut self, old: VideoSwitches) {
+        let new = self.mmu.video_switches();
+        if new != old {
+            self.video_log
+                .record(self.cycle, VideoEvent::Switches { old, new });
+        }
+    }
+
+    fn write_ram(&mut self, addr: u16, value: u8) {
+        let bank = self.mmu.write_bank(addr);
+        let old = self.bank(bank)[addr as usize];
+        if old != value && is_display_addr(addr) {
+            let event = VideoEvent::Write {
+                addr,
+                aux: bank == Bank::Aux,
+                old,
+                new: value,
+            };
+            self.video_log.record(self.cycle, event);
+        }
+        self.bank_mut(bank)[addr as usize] = value;
+    }
+
+    fn access_io(&mut self, addr: u16, write: bool) {
+        let switches = self.mmu.video_switches();
+        self.switch_io(addr, write);
+        self.log_switches(switches);
+    }
+
+    fn switch_io(&mut self, addr: u16, write: bool) {
+        match addr & 0xff {
+            0x00..=0x0f if write && self.model == MemoryModel::Iie => self.mmu.write_switch(addr),
+            0x10..=0x1f if self.model == MemoryModel::IiPlus => self.keyboard_latch &= 0x7f,
+            0x10..=0x1f if write || (addr & 0xff) == 0x10 => self.keyboard_latch &= 0x7f,
+            0x50..=0x57 => self.mmu.access_display_switch(addr),
+            0x5e..=0x5f if self.model == MemoryModel::Iie => self.mmu.access_an3(addr),
+            0x80..=0x8f => self.language_card.access(addr, write),
+            _ => {}
+        }
+    }
+}
+
+impl Bus for IieMemory {
+    fn read(&mut self, addr: u16, cycle: u64) -> u8 {
+        self.cycle = cycle;
+        self.video_log.advance(cycle);
+        match addr {
+            0x0000..=0xbfff => self.bank(self.mmu.read_bank(addr))[addr as usize],
+            0xc000..=0xc0ff => {
+                let v = self.peek_io(addr);
+                self.access_io(addr, false);
+                v
+            }
+            0xd000..=0xffff => self.peek_high(addr),
+            _ => {
+                self.touch_firmware(addr);
+                self.peek_firmware(addr)
+            }
+        }
+    }
+
+    fn write(&mut self, addr: u16, value: u8, cycle: u64) {
+        self.cycle = cycle;
+        self.video_log.advance(cycle);
+        match addr {
+            0x0000..=0xbfff => self.write_ram(addr, value),
+            0xc000..=0xc0ff => self.access_io(addr, true),
+            0xd000..=0xffff => self.write_high(addr, value),
+            _ => self.touch_firmware(addr),
+        }
+    }
+
+    fn peek(&self, addr: u16) -> u8 {
+        match addr {
+            0x0000..=0xbfff => self.bank(self.mmu.read_bank(addr))[addr as usize],
+            0xc000..=0xc0ff => self.peek_io(addr),
+            0xd000..=0xffff => self.peek_high(addr),
+            _ => self.peek_firmware(addr),
+        }
+    }
+
+    fn peek_display(&self, addr: u16, aux: bool) -> u8 {
+        let bank = if aux { Bank::Aux } else { Bank::Main };
+        self.bank(bank)[addr as usize]
+    }
+
+    fn video_switches(&self) -> Option<VideoSwitches> {
+        Some(self.mmu.video_switches())
+    }
+
+    fn video_log(&self) -> Option<&VideoLog> {
+        Some(&self.video_log)
+    }
+}
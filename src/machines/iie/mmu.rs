@@ -0,0 +1,142 @@
+use crate::video::VideoSwitches;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bank {
+    Main,
+    Aux,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mmu {
+    pub store80: bool,
+    pub ramrd: bool,
+    pub ramwrt: bool,
+    pub altzp: bool,
+    pub intcxrom: bool,
+    pub slotc3rom: bool,
+    pub col80: bool,
+    pub altcharset: bool,
+    pub text: bool,
+    pub mixed: bool,
+    pub page2: bool,
+    pub hires: bool,
+    // AN3 low ($C05E) with 80COL on selects the double-resolution modes.
+    pub dhires: bool,
+}
+
+impl Default for Mmu {
+    fn default() -> Self {
+        Self {
+            store80: false,
+            ramrd: false,
+            ramwrt: false,
+            altzp: false,
+            intcxrom: false,
+            slotc3rom: false,
+            col80: false,
+            altcharset: false,
+            // The monitor's reset handler leaves the display in text mode.
+            text: true,
+            mixed: false,
+            page2: false,
+            hires: false,
+            dhires: false,
+        }
+    }
+}
+
+impl Mmu {
+    // RESET clears the memory-management switches; the display switches are left
+    // for the firmware to set up.
+    pub fn reset(&mut self) {
+        let display = (self.text, self.mixed, self.page2, self.hires, self.dhires);
+        *self = Self::default();
+        (self.text, self.mixed, self.page2, self.hires, self.dhires) = display;
+    }
+
+    // $C000-$C00F: write-only MMU/IOU switches, even address clears, odd sets.
+    pub fn write_switch(&mut self, addr: u16) {
+        let on = (addr & 0x01) != 0;
+        match addr & 0x0e {
+            0x00 => self.store80 = on,
+            0x02 => self.ramrd = on,
+            0x04 => self.ramwrt = on,
+            0x06 => self.intcxrom = on,
+            0x08 => self.altzp = on,
+            0x0a => self.slotc3rom = on,
+            0x0c => self.col80 = on,
+            _ => self.altcharset = on,
+        }
+    }
+
+    // $C050-$C057: display switches toggled by any read or write.
+    pub fn access_display_switch(&mut self, addr: u16) {
+        let on = (addr & 0x01) != 0;
+        match addr & 0x06 {
+            0x00 => self.text = on,
+            0x02 => self.mixed = on,
+            0x04 => self.page2 = on,
+            _ => self.hires = on,
+        }
+    }
+
+    // $C05E/$C05F: AN3 off enables double hi-res and double lo-res.
+    pub fn access_an3(&mut self, addr: u16) {
+        self.dhires = (addr & 0x01) == 0;
+    }
+
+    // $C013-$C01F: returns the flag reported in bit 7, or None for addresses owned elsewhere.
+    pub fn status(&self, addr: u16) -> Option<bool> {
+        match addr & 0xff {
+            0x13 => Some(self.ramrd),
+            0x14 => Some(self.ramwrt),
+            0x15 => Some(self.intcxrom),
+            0x16 => Some(self.altzp),
+            0x17 => Some(self.slotc3rom),
+            0x18 => Some(self.store80),
+            0x1a => Some(self.text),
+            0x1b => Some(self.mixed),
+            0x1c => Some(self.page2),
+            0x1d => Some(self.hires),
+            0x1e => Some(self.altcharset),
+            0x1f => Some(self.col80),
+            _ => None,
+        }
+    }
+
+    pub fn read_bank(&self, addr: u16) -> Bank {
+        self.ram_bank(addr, self.ramrd)
+    }
+
+    pub fn write_bank(&self, addr: u16) -> Bank {
+        self.ram_bank(addr, self.ramwrt)
+    }
+
+    // Selects the 64K bank for the language card area ($D000-$FFFF).
+    pub fn high_bank(&self) -> Bank {
+        if self.altzp { Bank::Aux } else { Bank::Main }
+    }
+
+    pub fn video_switches(&self) -> VideoSwitches {
+        VideoSwitches {
+            text: self.text,
+            mixed: self.mixed,
+            page2: self.page2,
+            hires: self.hires,
+            col80: self.col80,
+            store80: self.store80,
+            altcharset: self.altcharset,
+            dhires: self.dhires,
+        }
+    }
+
+    fn ram_bank(&self, addr: u16, aux_selected: bool) -> Bank {
+        let aux = match addr {
+            0x0000..=0x01ff => self.altzp,
+            0x0400..=0x07ff if self.store80 => self.page2,
+            0x2000..=0x3fff if self.store80 && self.hires => self.page2,
+            _ => aux_selected,
+        };
+        if aux { Bank::Aux } else { Bank::Main }
+    }
+}
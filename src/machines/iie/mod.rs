@@ -0,0 +1,21 @@
+pub mod language_card;
+pub mod memory;
+pub mod mmu;
+
+use super::Machine;
+use super::ram_init::RamInit;
+use crate::cpu::Cpu;
+use crate::scheduler::{MasterClock, Scheduler};
+use memory::IieMemory;
+
+pub fn apple_iie() -> Machine {
+    Machine {
+        name: "Apple IIe",
+        description: "First machine in EchoLab. Focus: accurate 65C02 core and timing.",
+        cpu: Cpu::new(),
+        memory: IieMemory::new(),
+        ram_init: RamInit::default(),
+        clock: MasterClock::default(),
+        events: Scheduler::new(),
+    }
+}
@@ -1,6 +1,167 @@
+pub mod ii_plus;
 pub mod iie;
+pub mod ram_init;
+
+use crate::bus::Bus;
+use crate::cpu::Cpu;
+use crate::rom::SystemRom;
+use crate::scheduler::{EventId, MasterClock, Scheduler};
+use crate::video::VideoSwitches;
+use crate::video::log::VideoLog;
+use crate::video::scanner::BeamPosition;
+use iie::memory::IieMemory;
+use ram_init::RamInit;
+
+pub const NTSC_CYCLES_PER_FRAME: u64 = 17_030;
+
+// A device callback, run at the first instruction boundary at or after its
+// due cycle; the due cycle is passed so handlers can correct for lateness.
+pub type DeviceEvent = fn(&mut Machine, u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MachineKind {
+    #[default]
+    Iie,
+    IiPlus,
+}
+
+impl MachineKind {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "iie" => Ok(Self::Iie),
+            "iiplus" | "ii+" => Ok(Self::IiPlus),
+            other => Err(format!(
+                "unknown machine '{}': expected iie or iiplus",
+                other
+            )),
+        }
+    }
+
+    pub fn build(self) -> Machine {
+        match self {
+            Self::Iie => iie::apple_iie(),
+            Self::IiPlus => ii_plus::apple_ii_plus(),
+        }
+    }
+}
 
 pub struct Machine {
     pub name: &'static str,
     pub description: &'static str,
+    pub cpu: Cpu,
+    pub memory: IieMemory,
+    pub ram_init: RamInit,
+    pub clock: MasterClock,
+    pub events: Scheduler<DeviceEvent>,
+}
+
+impl Machine {
+    pub fn with_ram_init(mut self, init: RamInit) -> Self {
+        self.ram_init = init;
+        self.memory.initialize_ram(init);
+        self
+    }
+
+    pub fn load_rom(&mut self, rom: &SystemRom) {
+        self.memory.load_rom(rom);
+    }
+
+    // Cold start: RAM refilled from `ram_init`, fresh CPU state (of the same
+    // variant) and soft switches, then the RESET vector fetch. The cycle count
+    // restarts at zero, so pending device events are dropped.
+    pub fn power_on(&mut self) -> u32 {
+        self.memory.initialize_ram(self.ram_init);
+        self.memory.power_on();
+        self.cpu = Cpu::new().with_variant(self.cpu.variant());
+        self.clock.reset();
+        self.events.clear();
+        self.cpu.reset(&mut self.memory)
+    }
+
+    // Warm reset (Ctrl-Reset): RAM is preserved.
+    pub fn reset(&mut self) -> u32 {
+        self.memory.reset();
+        self.cpu.reset(&mut self.memory)
+    }
+
+    pub fn set_irq(&mut self, line: u8, asserted: bool) {
+        self.cpu.set_irq_line(line, asserted);
+    }
+
+    pub fn set_nmi(&mut self, asserted: bool) {
+        self.cpu.set_nmi_line(asserted);
+    }
+
+    // The beam as of the next cycle the CPU will run.
+    pub fn beam_position(&self) -> BeamPosition {
+        BeamPosition::at(self.cpu.cycles)
+    }
+
+    pub fn schedule_in(&mut self, cycles: u64, event: DeviceEvent) -> EventId {
+        self.events.schedule(self.cpu.cycles + cycles, event)
+    }
+
+    pub fn dispatch_events(&mut self) {
+        while let Some((due, event)) = self.events.pop_due(self.cpu.cycles) {
+            event(self, due);
+        }
+    }
+
+    pub fn step(&mut self) -> u32 {
+        self.dispatch_events();
+        self.cpu.step(&mut self.memory)
+    }
+
+    // Runs whole instructions until at least `cycles` have elapsed; returns the
+    // cycles actually executed.
+    pub fn run_cycles(&mut self, cycles: u64) -> u64 {
+        let start = self.cpu.cycles;
+        let target = start.saturating_add(cycles);
+        while self.cpu.cycles < target {
+            self.step();
+        }
+        self.cpu.cycles - start
+    }
+
+    pub fn run_frames(&mut self, frames: usize) -> u64 {
+        self.run_cycles((frames as u64).saturating_mul(NTSC_CYCLES_PER_FRAME))
+    }
+
+    // Advances the master clock by `ticks` (14.318 MHz) and runs the CPU up to
+    // the matching cycle. Overshoot from the last instruction is absorbed by
+    // the next call; returns the cycles executed.
+    pub fn run_master_ticks(&mut self, ticks: u64) -> u64 {
+        let target = self.clock.advance(ticks);
+        let start = self.cpu.cycles;
+        while self.cpu.cycles < target {
+            self.step();
+        }
+        self.cpu.cycles - start
+    }
+}
+
+impl Bus for Machine {
+    fn read(&mut self, addr: u16, cycle: u64) -> u8 {
+        self.memory.read(addr, cycle)
+    }
+
+    fn write(&mut self, addr: u16, value: u8, cycle: u64) {
+        self.memory.write(addr, value, cycle);
+    }
+
+    fn peek(&self, addr: u16) -> u8 {
+        self.memory.peek(addr)
+    }
+
+    fn peek_display(&self, addr: u16, aux: bool) -> u8 {
+        self.memory.peek_display(addr, aux)
+    }
+
+    fn video_switches(&self) -> Option<VideoSwitches> {
+        self.memory.video_switches()
+    }
+
+    fn video_log(&self) -> Option<&VideoLog> {
+        self.memory.video_log()
+    }
 }
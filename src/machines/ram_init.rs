@@ -0,0 +1,70 @@
+use crate::rng::FastRng;
+
+pub const DEFAULT_RAM_SEED: u64 = 0x6502_1983;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RamInit {
+    #[default]
+    Zeros,
+    Ones,
+    // FF FF 00 00 repeating, the striped pattern typical of IIe DRAM at power-on.
+    IieStripes,
+    Random(u64),
+}
+
+impl RamInit {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        match value {
+            "zeros" => Ok(Self::Zeros),
+            "ones" => Ok(Self::Ones),
+            "iie" => Ok(Self::IieStripes),
+            "random" => Ok(Self::Random(DEFAULT_RAM_SEED)),
+            _ => {
+                let Some(seed) = value.strip_prefix("random:") else {
+                    return Err(format!(
+                        "unknown RAM init '{}' (expected zeros, ones, iie, random or random:<seed>)",
+                        value
+                    ));
+                };
+                parse_seed(seed)
+                    .map(Self::Random)
+                    .ok_or_else(|| format!("invalid RAM init seed '{}'", seed))
+            }
+        }
+    }
+
+    // Fills the banks in order; a random pattern continues one generator across
+    // all of them so the whole machine is reproducible from one seed.
+    pub fn fill(&self, banks: &mut [&mut [u8]]) {
+        let mut rng = match self {
+            Self::Random(seed) => Some(FastRng::new(*seed)),
+            _ => None,
+        };
+
+        for bank in banks.iter_mut() {
+            for (i, byte) in bank.iter_mut().enumerate() {
+                *byte = match self {
+                    Self::Zeros => 0x00,
+                    Self::Ones => 0xff,
+                    Self::IieStripes => {
+                        if (i & 0x02) == 0 {
+                            0xff
+                        } else {
+                            0x00
+                        }
+                    }
+                    Self::Random(_) => rng.as_mut().map_or(0, FastRng::next_u8),
+                };
+            }
+        }
+    }
+}
+
+fn parse_seed(raw: &str) -> Option<u64> {
+    let raw = raw.trim();
+    match raw.strip_prefix("0x").or_else(|| raw.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(&hex.replace('_', ""), 16).ok(),
+        None => raw.replace('_', "").parse().ok(),
+    }
+}
@@ -1,8 +1,20 @@
 use echo_lab::lab::Lab;
-use echo_lab::machines::iie;
+use echo_lab::machines::{ii_plus, iie};
+use echo_lab::runner::{RUN_USAGE, RunOptions, run_headless};
 
 fn main() {
-    let lab = Lab::new("EchoLab").with_machine(iie::apple_iie());
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("run") {
+        if let Err(err) = run(&args[1..]) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let lab = Lab::new("EchoLab")
+        .with_machine(iie::apple_iie())
+        .with_machine(ii_plus::apple_ii_plus());
 
     println!("{}", lab.title());
     println!("Machines in lab:");
@@ -12,3 +24,53 @@ fn main() {
         println!("   {}", machine.description);
     }
 }
+
+fn run(args: &[String]) -> Result<(), String> {
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{}", RUN_USAGE);
+        return Ok(());
+    }
+
+    let options = RunOptions::parse(args)?;
+    if !options.headless {
+        return run_windowed(options);
+    }
+
+    let summary = run_headless(&options)?;
+    println!(
+        "Stopped after {} cycles at PC=${:04X}",
+        summary.cycles, summary.pc
+    );
+    if let Some(path) = &options.output {
+        println!("Saved frame to {}", path);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "sdl3")]
+fn run_windowed(options: RunOptions) -> Result<(), String> {
+    use echo_lab::config::EchoLabConfig;
+    use echo_lab::sdl_display_core::{SdlDisplayCoreOptions, run_machine_display};
+
+    let cfg = EchoLabConfig::load_from_path(&options.config_path, options.config_path_explicit)?;
+    let machine = echo_lab::runner::boot_machine(&options, &cfg)?;
+    let core_options = SdlDisplayCoreOptions {
+        title: "EchoLab".to_owned(),
+        config_path: options.config_path,
+        config_path_explicit: options.config_path_explicit,
+        capture: options.capture,
+        geometry: options.geometry,
+        hires_color: options.hires_color,
+        video: options.video,
+        ..SdlDisplayCoreOptions::default()
+    };
+    run_machine_display(core_options, machine)
+}
+
+#[cfg(not(feature = "sdl3"))]
+fn run_windowed(_options: RunOptions) -> Result<(), String> {
+    Err(format!(
+        "windowed mode requires the 'sdl3' feature; pass --headless\n{}",
+        RUN_USAGE
+    ))
+}
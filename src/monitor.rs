@@ -0,0 +1,463 @@
+use crate::bus::Bus;
+use crate::cpu::Cpu;
+use crate::cpu::asm::Assembler;
+use crate::cpu::disasm;
+use crate::machines::Machine;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+
+pub const DEFAULT_RUN_BUDGET: u64 = 10_230_000;
+
+pub const MONITOR_HELP: &str = "\
+s [n]                 step n instructions (default 1)
+n                     step over (runs JSR calls to completion)
+g [addr]              run until a breakpoint or the cycle budget
+t <addr>              run to address
+r [reg=val ...]       show or edit registers (a x y sp pc p)
+d <addr> [n]          disassemble n instructions (default 10)
+a <addr> <instr>      assemble one instruction into memory
+m <addr> [len]        dump memory
+f <start> <end> <val> fill memory
+find <start> <end> <byte>...
+                      search memory for a byte sequence
+b <addr>              break at address
+b op <byte>           break before an opcode executes
+b cycle <n>           break once the cycle counter reaches n
+bl                    list breakpoints
+bc [index]            clear one or all breakpoints
+q                     leave the monitor";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breakpoint {
+    Address(u16),
+    Opcode(u8),
+    Cycle(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    Breakpoint(Breakpoint),
+    Budget,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    pub text: String,
+    pub quit: bool,
+}
+
+pub struct Monitor {
+    breakpoints: Vec<Breakpoint>,
+    run_budget: u64,
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Monitor {
+    pub fn new() -> Self {
+        Self {
+            breakpoints: Vec::new(),
+            run_budget: DEFAULT_RUN_BUDGET,
+        }
+    }
+
+    pub fn with_run_budget(mut self, cycles: u64) -> Self {
+        self.run_budget = cycles;
+        self
+    }
+
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        self.breakpoints.as_slice()
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    // Cycle breakpoints are one-shot; address and opcode breakpoints persist.
+    pub fn check_breakpoints(&mut self, machine: &Machine) -> Option<Breakpoint> {
+        let pc = machine.cpu.pc;
+        let opcode = machine.peek(pc);
+        let cycles = machine.cpu.cycles;
+        let hit = self.breakpoints.iter().copied().find(|bp| match *bp {
+            Breakpoint::Address(addr) => addr == pc,
+            Breakpoint::Opcode(op) => op == opcode,
+            Breakpoint::Cycle(n) => cycles >= n,
+        })?;
+        if matches!(hit, Breakpoint::Cycle(_)) {
+            self.breakpoints.retain(|bp| *bp != hit);
+        }
+        Some(hit)
+    }
+
+    // Runs at least one instruction, then stops before any instruction that hits a
+    // breakpoint or once `budget` cycles have elapsed.
+    pub fn run(&mut self, machine: &mut Machine, budget: u64) -> StopReason {
+        let target = machine.cpu.cycles.saturating_add(budget);
+        machine.step();
+        while machine.cpu.cycles < target {
+            if let Some(bp) = self.check_breakpoints(machine) {
+                return StopReason::Breakpoint(bp);
+            }
+            machine.step();
+        }
+        StopReason::Budget
+    }
+
+    pub fn execute(&mut self, machine: &mut Machine, line: &str) -> Result<Reply, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = words.split_first() else {
+            return Ok(reply(String::new()));
+        };
+
+        let text = match command {
+            "s" | "step" => {
+                let count = match args.first() {
+                    Some(n) => parse_number(n)?,
+                    None => 1,
+                };
+                let mut text = String::new();
+                for _ in 0..count {
+                    let _ = writeln!(text, "{}", describe_instruction(machine, machine.cpu.pc));
+                    machine.step();
+                }
+                text + &format_registers(&machine.cpu)
+            }
+            "n" | "over" => {
+                let pc = machine.cpu.pc;
+                let is_jsr = machine.peek(pc) == 0x20;
+                let stop = if is_jsr {
+                    self.run_to(machine, pc.wrapping_add(3))
+                } else {
+                    machine.step();
+                    None
+                };
+                self.stop_text(stop, machine)
+            }
+            "g" | "go" => {
+                if let Some(addr) = args.first() {
+                    machine.cpu.pc = parse_addr(addr)?;
+                }
+                let stop = self.run(machine, self.run_budget);
+                self.stop_text(Some(stop), machine)
+            }
+            "t" | "to" => {
+                let addr = parse_addr(args.first().ok_or("usage: t <addr>")?)?;
+                let stop = self.run_to(machine, addr);
+                self.stop_text(stop, machine)
+            }
+            "r" | "regs" => {
+                for assignment in args {
+                    set_register(&mut machine.cpu, assignment)?;
+                }
+                format_registers(&machine.cpu)
+            }
+            "m" | "mem" => {
+                let start = parse_addr(args.first().ok_or("usage: m <addr> [len]")?)?;
+                let len = match args.get(1) {
+                    Some(n) => parse_number(n)?,
+                    None => 0x40,
+                };
+                dump_memory(machine, start, len)
+            }
+            "f" | "fill" => {
+                let [start, end, value] = args else {
+                    return Err("usage: f <start> <end> <val>".to_owned());
+                };
+                let (start, end, value) =
+                    (parse_addr(start)?, parse_addr(end)?, parse_byte(value)?);
+                if start > end {
+                    return Err("usage: f <start> <end> <val>".to_owned());
+                }
+                for addr in start..=end {
+                    machine.write(addr, value, machine.cpu.cycles);
+                }
+                format!("filled ${:04X}-${:04X} with ${:02X}", start, end, value)
+            }
+            "find" => {
+                let [start, end, pattern @ ..] = args else {
+                    return Err("usage: find <start> <end> <byte>...".to_owned());
+                };
+                let pattern = pattern
+                    .iter()
+                    .map(|b| parse_byte(b))
+                    .collect::<Result<Vec<u8>, String>>()?;
+                if pattern.is_empty() {
+                    return Err("usage: find <start> <end> <byte>...".to_owned());
+                }
+                let hits = search_memory(machine, parse_addr(start)?, parse_addr(end)?, &pattern);
+                if hits.is_empty() {
+                    "not found".to_owned()
+                } else {
+                    hits.iter()
+                        .map(|a| format!("${:04X}", a))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                }
+            }
+            "b" | "break" => {
+                let bp = match args {
+                    ["op", value] => Breakpoint::Opcode(parse_byte(value)?),
+                    ["cycle", value] => Breakpoint::Cycle(parse_number(value)? as u64),
+                    [addr] => Breakpoint::Address(parse_addr(addr)?),
+                    _ => return Err("usage: b <addr> | b op <byte> | b cycle <n>".to_owned()),
+                };
+                self.add_breakpoint(bp);
+                let index = self.breakpoints.iter().position(|b| *b == bp).unwrap_or(0);
+                format!("breakpoint {}: {}", index, describe_breakpoint(bp))
+            }
+            "bl" => {
+                if self.breakpoints.is_empty() {
+                    "no breakpoints".to_owned()
+                } else {
+                    self.breakpoints
+                        .iter()
+                        .enumerate()
+                        .map(|(i, bp)| format!("{}: {}", i, describe_breakpoint(*bp)))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+            }
+            "bc" => match args.first() {
+                Some(index) => {
+                    let index = parse_number(index)?;
+                    if index >= self.breakpoints.len() {
+                        return Err(format!("no breakpoint {}", index));
+                    }
+                    self.breakpoints.remove(index);
+                    format!("cleared breakpoint {}", index)
+                }
+                None => {
+                    self.breakpoints.clear();
+                    "cleared all breakpoints".to_owned()
+                }
+            },
+            "d" | "dis" => {
+                let start = parse_addr(args.first().ok_or("usage: d <addr> [n]")?)?;
+                let count = match args.get(1) {
+                    Some(n) => parse_number(n)?,
+                    None => 10,
+                };
+                disasm::disassemble_range_for(machine.cpu.variant(), machine, start, count)
+                    .iter()
+                    .map(|i| describe_instruction(machine, i.addr))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            "a" | "asm" => {
+                let [addr, instruction @ ..] = args else {
+                    return Err("usage: a <addr> <instr>".to_owned());
+                };
+                let addr = parse_addr(addr)?;
+                let bytes = Assembler::new()
+                    .with_variant(machine.cpu.variant())
+                    .assemble_line(addr, &instruction.join(" "))?;
+                for (offset, byte) in bytes.iter().enumerate() {
+                    machine.write(addr.wrapping_add(offset as u16), *byte, machine.cpu.cycles);
+                }
+                describe_instruction(machine, addr)
+            }
+            "h" | "help" | "?" => MONITOR_HELP.to_owned(),
+            "q" | "quit" => {
+                return Ok(Reply {
+                    text: String::new(),
+                    quit: true,
+                });
+            }
+            other => return Err(format!("unknown command '{}' (h for help)", other)),
+        };
+
+        Ok(reply(text))
+    }
+
+    pub fn run_repl<R: BufRead, W: Write>(
+        &mut self,
+        machine: &mut Machine,
+        mut input: R,
+        mut output: W,
+    ) -> io::Result<()> {
+        writeln!(output, "{}", format_registers(&machine.cpu))?;
+        let mut line = String::new();
+        loop {
+            write!(output, "* ")?;
+            output.flush()?;
+            line.clear();
+            if input.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            match self.execute(machine, &line) {
+                Ok(reply) if reply.quit => return Ok(()),
+                Ok(reply) if reply.text.is_empty() => {}
+                Ok(reply) => writeln!(output, "{}", reply.text)?,
+                Err(err) => writeln!(output, "error: {}", err)?,
+            }
+        }
+    }
+
+    fn run_to(&mut self, machine: &mut Machine, addr: u16) -> Option<StopReason> {
+        let target = machine.cpu.cycles.saturating_add(self.run_budget);
+        machine.step();
+        while machine.cpu.pc != addr {
+            if machine.cpu.cycles >= target {
+                return Some(StopReason::Budget);
+            }
+            if let Some(bp) = self.check_breakpoints(machine) {
+                return Some(StopReason::Breakpoint(bp));
+            }
+            machine.step();
+        }
+        None
+    }
+
+    fn stop_text(&self, stop: Option<StopReason>, machine: &Machine) -> String {
+        let registers = format_registers(&machine.cpu);
+        match stop {
+            Some(StopReason::Breakpoint(bp)) => {
+                format!("hit {}\n{}", describe_breakpoint(bp), registers)
+            }
+            Some(StopReason::Budget) => format!(
+                "stopped after {} cycle budget\n{}",
+                self.run_budget, registers
+            ),
+            None => registers,
+        }
+    }
+}
+
+pub fn format_registers(cpu: &Cpu) -> String {
+    const NAMES: &[u8; 8] = b"NV-BDIZC";
+    let flags: String = NAMES
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            if (cpu.p & (0x80 >> i)) != 0 {
+                *name as char
+            } else {
+                (*name as char).to_ascii_lowercase()
+            }
+        })
+        .collect();
+    format!(
+        "PC={:04X} A={:02X} X={:02X} Y={:02X} SP={:02X} P={:02X} {} CYC={}",
+        cpu.pc, cpu.a, cpu.x, cpu.y, cpu.sp, cpu.p, flags, cpu.cycles
+    )
+}
+
+pub fn describe_instruction(machine: &Machine, pc: u16) -> String {
+    let instruction = disasm::disassemble_for(machine.cpu.variant(), machine, pc);
+    let bytes: Vec<String> = instruction
+        .bytes()
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect();
+    format!("{:04X}: {:<9} {}", pc, bytes.join(" "), instruction)
+}
+
+fn describe_breakpoint(bp: Breakpoint) -> String {
+    match bp {
+        Breakpoint::Address(addr) => format!("address ${:04X}", addr),
+        Breakpoint::Opcode(op) => format!("opcode ${:02X}", op),
+        Breakpoint::Cycle(n) => format!("cycle {}", n),
+    }
+}
+
+fn dump_memory(machine: &Machine, start: u16, len: usize) -> String {
+    let mut text = String::new();
+    for row in 0..len.div_ceil(16) {
+        let base = start.wrapping_add((row * 16) as u16);
+        let count = (len - row * 16).min(16);
+        let bytes: Vec<u8> = (0..count)
+            .map(|i| machine.peek(base.wrapping_add(i as u16)))
+            .collect();
+        let hex: Vec<String> = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+        let ascii: String = bytes
+            .iter()
+            .map(|b| {
+                let c = b & 0x7f;
+                if (0x20..0x7f).contains(&c) {
+                    c as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        if row > 0 {
+            text.push('\n');
+        }
+        let _ = write!(text, "{:04X}: {:<47}  {}", base, hex.join(" "), ascii);
+    }
+    text
+}
+
+fn search_memory(machine: &Machine, start: u16, end: u16, pattern: &[u8]) -> Vec<u16> {
+    let mut hits = Vec::new();
+    let mut addr = start as u32;
+    while addr + pattern.len() as u32 - 1 <= end as u32 {
+        let matched = pattern
+            .iter()
+            .enumerate()
+            .all(|(i, b)| machine.peek((addr + i as u32) as u16) == *b);
+        if matched {
+            hits.push(addr as u16);
+        }
+        addr += 1;
+    }
+    hits
+}
+
+fn set_register(cpu: &mut Cpu, assignment: &str) -> Result<(), String> {
+    let Some((name, value)) = assignment.split_once('=') else {
+        return Err(format!("expected reg=value, got '{}'", assignment));
+    };
+    match name.to_ascii_lowercase().as_str() {
+        "a" => cpu.a = parse_byte(value)?,
+        "x" => cpu.x = parse_byte(value)?,
+        "y" => cpu.y = parse_byte(value)?,
+        "sp" | "s" => cpu.sp = parse_byte(value)?,
+        "p" => cpu.p = parse_byte(value)?,
+        "pc" => cpu.pc = parse_addr(value)?,
+        other => return Err(format!("unknown register '{}'", other)),
+    }
+    Ok(())
+}
+
+fn reply(text: String) -> Reply {
+    Reply { text, quit: false }
+}
+
+// Monitor addresses and bytes are hex, with an optional `$` or `0x` prefix.
+fn parse_hex(raw: &str) -> Option<u32> {
+    let hex = raw
+        .strip_prefix('$')
+        .or_else(|| raw.strip_prefix("0x"))
+        .unwrap_or(raw);
+    u32::from_str_radix(hex, 16).ok()
+}
+
+fn parse_addr(raw: &str) -> Result<u16, String> {
+    parse_hex(raw)
+        .and_then(|v| u16::try_from(v).ok())
+        .ok_or_else(|| format!("invalid address '{}'", raw))
+}
+
+fn parse_byte(raw: &str) -> Result<u8, String> {
+    parse_hex(raw)
+        .and_then(|v| u8::try_from(v).ok())
+        .ok_or_else(|| format!("invalid byte '{}'", raw))
+}
+
+// Counts are decimal unless prefixed with `$` or `0x`.
+fn parse_number(raw: &str) -> Result<usize, String> {
+    let value = if raw.starts_with('$') || raw.starts_with("0x") {
+        parse_hex(raw).map(|v| v as usize)
+    } else {
+        raw.replace('_', "").parse().ok()
+    };
+    value.ok_or_else(|| format!("invalid number '{}'", raw))
+}
@@ -0,0 +1,266 @@
+use crate::checksum::{crc32, sha1, to_hex};
+use crate::config::SystemRomConfig;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct KnownRom {
+    pub part: &'static str,
+    pub description: &'static str,
+    pub base: u16,
+    pub len: usize,
+    pub crc32: u32,
+    pub sha1: &'static str,
+}
+
+impl KnownRom {
+    // Both hashes have to agree; a CRC32 collision alone is not a match.
+    pub fn matches(&self, data: &[u8]) -> bool {
+        data.len() == self.len && crc32(data) == self.crc32 && to_hex(&sha1(data)) == self.sha1
+    }
+}
+
+// Entries can be single chips or whole images; identification takes the
+// longest match at each address, so a 16K or 12K dump matches as well as the
+// chips it was read from. Hashes follow MAME's apple2 drivers.
+pub static KNOWN_ROMS: &[KnownRom] = &[
+    KnownRom {
+        part: "342-0135-b",
+        description: "Apple IIe CD ROM",
+        base: 0xc000,
+        len: 0x2000,
+        crc32: 0xe248_835e,
+        sha1: "523838c19c79f481fcbc52ef4ce12c7c7e84ff84",
+    },
+    KnownRom {
+        part: "342-0134-a",
+        description: "Apple IIe EF ROM",
+        base: 0xe000,
+        len: 0x2000,
+        crc32: 0xfc3d_59d8,
+        sha1: "8895a4b703f2184b673078f411f4089889b61c54",
+    },
+    KnownRom {
+        part: "342-0304-a",
+        description: "Enhanced Apple IIe CD ROM",
+        base: 0xc000,
+        len: 0x2000,
+        crc32: 0x443a_a7c4,
+        sha1: "3aecc56a26134df51e65e17f33ae80c1f1ac93e6",
+    },
+    KnownRom {
+        part: "342-0303-a",
+        description: "Enhanced Apple IIe EF ROM",
+        base: 0xe000,
+        len: 0x2000,
+        crc32: 0x95e1_0034,
+        sha1: "afb09bb96038232dc757d40c0605623cae38088e",
+    },
+    KnownRom {
+        part: "341-0011",
+        description: "Apple II+ Applesoft D0 ROM",
+        base: 0xd000,
+        len: 0x0800,
+        crc32: 0x6f05_f949,
+        sha1: "0287ebcef2c1ce11dc71be15a99d2d7e0e128b1e",
+    },
+    KnownRom {
+        part: "341-0012",
+        description: "Apple II+ Applesoft D8 ROM",
+        base: 0xd800,
+        len: 0x0800,
+        crc32: 0x1f08_087c,
+        sha1: "a75ce5aab6401355bf1ab01b04e4946a424879b5",
+    },
+    KnownRom {
+        part: "341-0013",
+        description: "Apple II+ Applesoft E0 ROM",
+        base: 0xe000,
+        len: 0x0800,
+        crc32: 0x2b8d_9a89,
+        sha1: "8d82a1da63224859bd619005fab62c4714b25dd7",
+    },
+    KnownRom {
+        part: "341-0014",
+        description: "Apple II+ Applesoft E8 ROM",
+        base: 0xe800,
+        len: 0x0800,
+        crc32: 0x5719_871a,
+        sha1: "37501be96d36d041667c15d63e0c1eff2f7dd4e9",
+    },
+    KnownRom {
+        part: "341-0015",
+        description: "Apple II+ Applesoft F0 ROM",
+        base: 0xf000,
+        len: 0x0800,
+        crc32: 0x9a04_eecf,
+        sha1: "e6bf91ed28464f42b807f798fb6422e5948bf581",
+    },
+    KnownRom {
+        part: "341-0020-00",
+        description: "Apple II+ Autostart Monitor ROM",
+        base: 0xf800,
+        len: 0x0800,
+        crc32: 0x0795_89c4,
+        sha1: "a28852ff997b4790e53d8d0352112c4b1a395098",
+    },
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RomSegment {
+    pub base: u16,
+    pub len: usize,
+    pub crc32: u32,
+    pub known: Option<&'static KnownRom>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemRom {
+    bytes: Vec<u8>,
+    base: u16,
+    crc32: u32,
+    sha1: [u8; 20],
+    segments: Vec<RomSegment>,
+}
+
+impl SystemRom {
+    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path_ref = path.as_ref();
+        let bytes = fs::read(path_ref)
+            .map_err(|e| format!("failed to read system ROM '{}': {}", path_ref.display(), e))?;
+        Self::from_bytes(bytes).map_err(|e| format!("system ROM '{}': {}", path_ref.display(), e))
+    }
+
+    pub fn load_from_config(cfg: &SystemRomConfig) -> Result<Option<Self>, String> {
+        let Some(path) = cfg.path.as_deref() else {
+            return Ok(None);
+        };
+        Self::load_verified(path, cfg.sha1.as_deref()).map(Some)
+    }
+
+    pub fn load_verified(path: &str, expected_sha1: Option<&str>) -> Result<Self, String> {
+        let rom = Self::load_from_path(path)?;
+        if let Some(expected) = expected_sha1 {
+            rom.verify_sha1(expected)
+                .map_err(|e| format!("system ROM '{}': {}", path, e))?;
+        }
+        Ok(rom)
+    }
+
+    // 16K images cover $C000-$FFFF; 12K images cover $D000-$FFFF.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, String> {
+        Self::from_bytes_with_known(bytes, KNOWN_ROMS)
+    }
+
+    pub fn from_bytes_with_known(
+        bytes: Vec<u8>,
+        known: &'static [KnownRom],
+    ) -> Result<Self, String> {
+        let base = match bytes.len() {
+            0x4000 => 0xc000,
+            0x3000 => 0xd000,
+            len => {
+                return Err(format!(
+                    "unexpected image size {} bytes (expected 16384 or 12288)",
+                    len
+                ));
+            }
+        };
+
+        let segments = identify_segments(&bytes, base, known);
+        Ok(Self {
+            crc32: crc32(&bytes),
+            sha1: sha1(&bytes),
+            bytes,
+            base,
+            segments,
+        })
+    }
+
+    pub fn verify_sha1(&self, expected: &str) -> Result<(), String> {
+        let actual = self.sha1_hex();
+        if actual.eq_ignore_ascii_case(expected.trim()) {
+            Ok(())
+        } else {
+            Err(format!(
+                "SHA-1 mismatch: expected {}, got {}",
+                expected.trim(),
+                actual
+            ))
+        }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    pub fn base(&self) -> u16 {
+        self.base
+    }
+
+    pub fn crc32(&self) -> u32 {
+        self.crc32
+    }
+
+    pub fn sha1_hex(&self) -> String {
+        to_hex(&self.sha1)
+    }
+
+    pub fn segments(&self) -> &[RomSegment] {
+        self.segments.as_slice()
+    }
+
+    pub fn is_recognized(&self) -> bool {
+        self.segments.iter().all(|s| s.known.is_some())
+    }
+
+    pub fn describe(&self) -> String {
+        let mut lines = vec![format!(
+            "system ROM ${:04X}-$FFFF crc32={:08x} sha1={}",
+            self.base,
+            self.crc32,
+            self.sha1_hex()
+        )];
+        for segment in &self.segments {
+            let end = segment.base as usize + segment.len - 1;
+            lines.push(match segment.known {
+                Some(known) => format!(
+                    "  ${:04X}-${:04X}: {} ({})",
+                    segment.base, end, known.part, known.description
+                ),
+                None => format!(
+                    "  ${:04X}-${:04X}: unrecognized (crc32={:08x})",
+                    segment.base, end, segment.crc32
+                ),
+            });
+        }
+        lines.join("\n")
+    }
+}
+
+fn identify_segments(bytes: &[u8], base: u16, known: &'static [KnownRom]) -> Vec<RomSegment> {
+    let mut segments = Vec::new();
+    let mut addr = base as usize;
+    let end = base as usize + bytes.len();
+    while addr < end {
+        let rest = &bytes[addr - base as usize..];
+        let found = known
+            .iter()
+            .filter(|k| k.base as usize == addr && k.len <= rest.len())
+            .filter(|k| k.matches(&rest[..k.len]))
+            .max_by_key(|k| k.len);
+        // Anything unmatched is reported up to the next 8K chip boundary.
+        let len = match found {
+            Some(k) => k.len,
+            None => (0x2000 - (addr & 0x1fff)).min(end - addr),
+        };
+        segments.push(RomSegment {
+            base: addr as u16,
+            len,
+            crc32: crc32(&rest[..len]),
+            known: found,
+        });
+        addr += len;
+    }
+    segments
+}
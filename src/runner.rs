@@ -0,0 +1,297 @@
+use crate::capture::CaptureOptions;
+use crate::config::EchoLabConfig;
+use crate::machines::ram_init::RamInit;
+use crate::machines::{Machine, MachineKind, NTSC_CYCLES_PER_FRAME};
+use crate::monitor::{Monitor, StopReason};
+use crate::rom::SystemRom;
+use crate::screen_buffer::ScreenBuffer;
+use crate::trace::{TraceFormat, Tracer};
+use crate::video::composite::VideoBackend;
+use crate::video::hires::HiresColor;
+use crate::video::{FrameGeometry, TextVideoController};
+use std::fs;
+use std::io;
+
+pub const RUN_USAGE: &str = "Usage: echolab run --headless (--cycles N | --frames N | --monitor) [--machine iie|iiplus] [--geometry scanlines|doubled|native] [--hires-color artifact|ideal|mono] [--video digital|composite] [--config <path>] [--rom <path>] [--ram-init <pattern>] [--load <addr>:<file>]... [--pc <addr>] [--output <file.ppm>] [--trace <file> [--trace-format native|nestest]] [--screenshot [dir]]";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunLimit {
+    Cycles(u64),
+    Frames(u64),
+}
+
+impl RunLimit {
+    pub fn cycles(&self) -> Result<u64, String> {
+        match self {
+            Self::Cycles(cycles) => Ok(*cycles),
+            Self::Frames(frames) => frames
+                .checked_mul(NTSC_CYCLES_PER_FRAME)
+                .ok_or_else(|| "--frames is too large".to_owned()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunOptions {
+    pub config_path: String,
+    pub config_path_explicit: bool,
+    pub headless: bool,
+    pub monitor: bool,
+    pub machine: MachineKind,
+    pub geometry: FrameGeometry,
+    pub hires_color: HiresColor,
+    pub video: VideoBackend,
+    pub limit: Option<RunLimit>,
+    pub rom_path: Option<String>,
+    pub ram_init: Option<RamInit>,
+    pub loads: Vec<(u16, String)>,
+    pub start_pc: Option<u16>,
+    pub output: Option<String>,
+    pub trace: Option<String>,
+    pub trace_format: TraceFormat,
+    pub capture: CaptureOptions,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            config_path: "echolab.toml".to_owned(),
+            config_path_explicit: false,
+            headless: false,
+            monitor: false,
+            machine: MachineKind::default(),
+            geometry: FrameGeometry::default(),
+            hires_color: HiresColor::default(),
+            video: VideoBackend::default(),
+            limit: None,
+            rom_path: None,
+            ram_init: None,
+            loads: Vec::new(),
+            start_pc: None,
+            output: None,
+            trace: None,
+            trace_format: TraceFormat::default(),
+            capture: CaptureOptions::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RunSummary {
+    pub cycles: u64,
+    pub pc: u16,
+    pub frame: ScreenBuffer,
+}
+
+impl RunOptions {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut i = 0usize;
+        while i < args.len() {
+            if options.capture.parse_arg(args, &mut i)? {
+                continue;
+            }
+
+            let flag = args[i].as_str();
+            match flag {
+                "--headless" => {
+                    options.headless = true;
+                    i += 1;
+                    continue;
+                }
+                "--monitor" => {
+                    options.monitor = true;
+                    i += 1;
+                    continue;
+                }
+                "-h" | "--help" => return Err(RUN_USAGE.to_owned()),
+                _ => {}
+            }
+
+            let Some(value) = args.get(i + 1) else {
+                return Err(format!("missing value for {}", flag));
+            };
+            match flag {
+                "--cycles" => options.set_limit(RunLimit::Cycles(parse_count(flag, value)?))?,
+                "--frames" => options.set_limit(RunLimit::Frames(parse_count(flag, value)?))?,
+                "--config" => {
+                    options.config_path = value.clone();
+                    options.config_path_explicit = true;
+                }
+                "--machine" => options.machine = MachineKind::parse(value)?,
+                "--geometry" => options.geometry = FrameGeometry::parse(value)?,
+                "--hires-color" => options.hires_color = HiresColor::parse(value)?,
+                "--video" => options.video = VideoBackend::parse(value)?,
+                "--rom" => options.rom_path = Some(value.clone()),
+                "--ram-init" => options.ram_init = Some(RamInit::parse(value)?),
+                "--load" => {
+                    let Some((addr, path)) = value.split_once(':') else {
+                        return Err(format!(
+                            "invalid --load '{}': expected <addr>:<file>",
+                            value
+                        ));
+                    };
+                    options.loads.push((parse_addr(addr)?, path.to_owned()));
+                }
+                "--pc" => options.start_pc = Some(parse_addr(value)?),
+                "--output" => options.output = Some(value.clone()),
+                "--trace" => options.trace = Some(value.clone()),
+                "--trace-format" => options.trace_format = TraceFormat::parse(value)?,
+                other => return Err(format!("unknown argument: {other}")),
+            }
+            i += 2;
+        }
+
+        if options.limit.is_none() && !options.monitor {
+            return Err(
+                "one of --cycles or --frames is required unless --monitor is given".to_owned(),
+            );
+        }
+        if options.trace.is_some() && options.monitor {
+            return Err("--trace cannot be combined with --monitor".to_owned());
+        }
+        Ok(options)
+    }
+
+    fn set_limit(&mut self, limit: RunLimit) -> Result<(), String> {
+        if self.limit.is_some() {
+            return Err("--cycles and --frames are mutually exclusive".to_owned());
+        }
+        self.limit = Some(limit);
+        Ok(())
+    }
+}
+
+pub fn boot_machine(options: &RunOptions, cfg: &EchoLabConfig) -> Result<Machine, String> {
+    let ram_init = options.ram_init.unwrap_or(cfg.machine.ram_init);
+    let mut machine = options.machine.build().with_ram_init(ram_init);
+
+    let rom = match options.rom_path.as_deref() {
+        Some(path) => Some(SystemRom::load_verified(
+            path,
+            cfg.system_rom.sha1.as_deref(),
+        )?),
+        None => SystemRom::load_from_config(&cfg.system_rom)?,
+    };
+    if let Some(rom) = &rom {
+        // Unknown images still boot, but say which segments did not match.
+        if !rom.is_recognized() {
+            eprintln!("warning: unrecognized system ROM\n{}", rom.describe());
+        }
+        machine.load_rom(rom);
+    }
+    machine.power_on();
+
+    for (addr, path) in &options.loads {
+        let bytes =
+            fs::read(path).map_err(|e| format!("failed to read program '{}': {}", path, e))?;
+        let start = *addr as usize;
+        if start + bytes.len() > 0xc000 {
+            return Err(format!(
+                "program '{}' ({} bytes) does not fit in RAM at ${:04X}",
+                path,
+                bytes.len(),
+                addr
+            ));
+        }
+        machine.memory.main_mut()[start..start + bytes.len()].copy_from_slice(&bytes);
+    }
+    if let Some(pc) = options.start_pc {
+        machine.cpu.pc = pc;
+    }
+
+    Ok(machine)
+}
+
+pub fn run_headless(options: &RunOptions) -> Result<RunSummary, String> {
+    let cfg = EchoLabConfig::load_from_path(&options.config_path, options.config_path_explicit)?;
+    let mut machine = boot_machine(options, &cfg)?;
+
+    if options.monitor {
+        let budget = options.limit.as_ref().map(RunLimit::cycles).transpose()?;
+        run_monitored(&mut machine, budget).map_err(|e| format!("monitor I/O failed: {}", e))?;
+    } else {
+        let cycles = match &options.limit {
+            Some(limit) => limit.cycles()?,
+            None => return Err("one of --cycles or --frames is required".to_owned()),
+        };
+        // Past the end of the cycle counter the run could never finish.
+        if machine.cpu.cycles.checked_add(cycles).is_none() {
+            return Err(format!(
+                "run limit of {} cycles overflows the cycle counter",
+                cycles
+            ));
+        }
+        match &options.trace {
+            Some(path) => {
+                let mut tracer = Tracer::to_file(path, options.trace_format)?;
+                tracer.run_cycles(&mut machine, cycles)?;
+                tracer.finish()?;
+            }
+            None => {
+                machine.run_cycles(cycles);
+            }
+        }
+    }
+
+    let backend = match cfg.composite {
+        Some(impairments) => options.video.with_impairments(impairments),
+        None => options.video,
+    };
+    let video = TextVideoController::default()
+        .with_geometry(options.geometry)
+        .with_hires_color(options.hires_color)
+        .with_backend(backend);
+    let (width, height) = video.frame_dimensions();
+    let mut frame = ScreenBuffer::new(width, height);
+    video.render_frame(&machine, machine.cpu.cycles, &mut frame);
+
+    if let Some(path) = &options.output {
+        frame
+            .save_as_ppm(path)
+            .map_err(|e| format!("failed to write '{}': {}", path, e))?;
+    }
+    options
+        .capture
+        .capture_frame_if_requested(&frame, &cfg.sdl3_text40x24.default_screenshot_dir)?;
+
+    Ok(RunSummary {
+        cycles: machine.cpu.cycles,
+        pc: machine.cpu.pc,
+        frame,
+    })
+}
+
+// Opens the monitor before the first instruction. Once it quits, the optional
+// limit runs with breakpoints armed, reopening the monitor on every hit.
+fn run_monitored(machine: &mut Machine, budget: Option<u64>) -> io::Result<()> {
+    let mut monitor = Monitor::new();
+    monitor.run_repl(machine, io::stdin().lock(), io::stdout())?;
+
+    let Some(budget) = budget else {
+        return Ok(());
+    };
+    let target = machine.cpu.cycles.saturating_add(budget);
+    while machine.cpu.cycles < target {
+        let remaining = target - machine.cpu.cycles;
+        if let StopReason::Breakpoint(_) = monitor.run(machine, remaining) {
+            monitor.run_repl(machine, io::stdin().lock(), io::stdout())?;
+        }
+    }
+    Ok(())
+}
+
+fn parse_count(flag: &str, raw: &str) -> Result<u64, String> {
+    raw.replace('_', "")
+        .parse::<u64>()
+        .map_err(|e| format!("invalid value for {}: {}", flag, e))
+}
+
+fn parse_addr(raw: &str) -> Result<u16, String> {
+    let raw = raw.trim();
+    let hex = raw
+        .strip_prefix('$')
+        .or_else(|| raw.strip_prefix("0x"))
+        .unwrap_or(raw);
+    u16::from_str_radix(hex, 16).map_err(|_| format!("invalid address '{}'", raw))
+}
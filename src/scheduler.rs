@@ -0,0 +1,155 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+// NTSC master oscillator: 315/22 MHz, four times the color subcarrier.
+pub const MASTER_CLOCK_HZ: f64 = 315_000_000.0 / 22.0;
+pub const MASTER_TICKS_PER_CYCLE: u64 = 14;
+// The last CPU cycle of every scanline is stretched by two master ticks so
+// each line stays in phase with the color subcarrier.
+pub const MASTER_TICKS_PER_LONG_CYCLE: u64 = 16;
+pub const CYCLES_PER_SCANLINE: u64 = 65;
+pub const SCANLINES_PER_FRAME: u64 = 262;
+pub const MASTER_TICKS_PER_SCANLINE: u64 =
+    (CYCLES_PER_SCANLINE - 1) * MASTER_TICKS_PER_CYCLE + MASTER_TICKS_PER_LONG_CYCLE;
+pub const MASTER_TICKS_PER_FRAME: u64 = MASTER_TICKS_PER_SCANLINE * SCANLINES_PER_FRAME;
+// Average CPU rate once the long cycle is accounted for (about 1.0205 MHz,
+// against the nominal 1.023 MHz).
+pub const CPU_CLOCK_HZ: f64 =
+    MASTER_CLOCK_HZ * CYCLES_PER_SCANLINE as f64 / MASTER_TICKS_PER_SCANLINE as f64;
+
+pub fn is_long_cycle(cycle: u64) -> bool {
+    cycle % CYCLES_PER_SCANLINE == CYCLES_PER_SCANLINE - 1
+}
+
+// Master tick at which CPU cycle `cycle` begins; cycle 0 starts scanline 0.
+pub fn cycle_to_master(cycle: u64) -> u64 {
+    let line = cycle / CYCLES_PER_SCANLINE;
+    let column = cycle % CYCLES_PER_SCANLINE;
+    line * MASTER_TICKS_PER_SCANLINE + column * MASTER_TICKS_PER_CYCLE
+}
+
+// Number of CPU cycles that have completed by master tick `ticks`.
+pub fn master_to_cycle(ticks: u64) -> u64 {
+    let line = ticks / MASTER_TICKS_PER_SCANLINE;
+    let offset = ticks % MASTER_TICKS_PER_SCANLINE;
+    line * CYCLES_PER_SCANLINE + (offset / MASTER_TICKS_PER_CYCLE).min(CYCLES_PER_SCANLINE - 1)
+}
+
+// Host-facing side of the clock: accumulates master ticks from the host loop
+// and turns them into a CPU cycle target. Ticks that do not complete a cycle
+// carry over to the next call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MasterClock {
+    ticks: u64,
+}
+
+impl MasterClock {
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    // Advances by `ticks` and returns the CPU cycle the guest should reach.
+    pub fn advance(&mut self, ticks: u64) -> u64 {
+        self.ticks += ticks;
+        master_to_cycle(self.ticks)
+    }
+
+    // Moves the clock to where CPU cycle `cycle` begins, e.g. after the guest
+    // ran outside of host pacing.
+    pub fn sync_to_cycle(&mut self, cycle: u64) {
+        self.ticks = cycle_to_master(cycle);
+    }
+
+    pub fn reset(&mut self) {
+        self.ticks = 0;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EventId(u64);
+
+struct Pending<E> {
+    due: u64,
+    id: u64,
+    event: E,
+}
+
+impl<E> PartialEq for Pending<E> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.due, self.id) == (other.due, other.id)
+    }
+}
+
+impl<E> Eq for Pending<E> {}
+
+impl<E> PartialOrd for Pending<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Reversed so the max-heap pops the earliest event; ties go in schedule order.
+impl<E> Ord for Pending<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.due, other.id).cmp(&(self.due, self.id))
+    }
+}
+
+// Future device events keyed by CPU cycle (VBL edges, disk bit cells, timers).
+pub struct Scheduler<E> {
+    queue: BinaryHeap<Pending<E>>,
+    next_id: u64,
+}
+
+impl<E> Default for Scheduler<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> Scheduler<E> {
+    pub fn new() -> Self {
+        Self {
+            queue: BinaryHeap::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn schedule(&mut self, due: u64, event: E) -> EventId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.queue.push(Pending { due, id, event });
+        EventId(id)
+    }
+
+    // Returns false when the event already fired or was cancelled.
+    pub fn cancel(&mut self, id: EventId) -> bool {
+        let before = self.queue.len();
+        self.queue.retain(|pending| pending.id != id.0);
+        self.queue.len() != before
+    }
+
+    pub fn next_due(&self) -> Option<u64> {
+        self.queue.peek().map(|pending| pending.due)
+    }
+
+    // Pops the earliest event due at or before `now`, with its due cycle.
+    pub fn pop_due(&mut self, now: u64) -> Option<(u64, E)> {
+        if self.next_due()? > now {
+            return None;
+        }
+        self.queue.pop().map(|pending| (pending.due, pending.event))
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+}
@@ -1,12 +1,22 @@
+use crate::bus::Bus;
 use crate::capture::CaptureOptions;
 use crate::config::EchoLabConfig;
+use crate::machines::Machine;
+use crate::monitor::{Monitor, StopReason};
 use crate::postfx::PersistenceBlend;
+use crate::scheduler::{MASTER_CLOCK_HZ, MASTER_TICKS_PER_FRAME};
 use crate::screen_buffer::ScreenBuffer;
 use crate::timing::{pace_to_next_frame, CrossoverSync};
+use crate::video::composite::VideoBackend;
+use crate::video::hires::HiresColor;
+use crate::video::scanner::CYCLES_PER_FRAME;
 use crate::video::{
-    COLOR_BLACK, COLOR_PHOSPHOR_GREEN, FRAME_HEIGHT, FRAME_WIDTH, TextVideoController,
+    COLOR_BLACK, COLOR_PHOSPHOR_GREEN, FRAME_HEIGHT, FRAME_WIDTH, FrameGeometry, TextAddressing,
+    TextVideoController,
 };
+use std::cell::RefCell;
 use std::ffi::{CStr, CString, c_char, c_int, c_void};
+use std::io;
 use std::ptr;
 use std::time::{Duration, Instant};
 
@@ -93,9 +103,59 @@ const SDL_WINDOW_RESIZABLE: u64 = 0x0000_0020;
 const SDL_TEXTUREACCESS_STREAMING: c_int = 1;
 const SDL_PIXELFORMAT_ARGB8888: u32 = 372_645_892;
 const SDL_EVENT_QUIT: u32 = 0x100;
-const APPLE2E_NTSC_FPS: f64 = 59.92;
+const SDL_EVENT_KEY_DOWN: u32 = 0x300;
+pub const SDLK_F11: u32 = 0x4000_0044;
+pub const SDLK_F12: u32 = 0x4000_0045;
+pub const SDLK_RIGHT: u32 = 0x4000_004f;
+pub const SDLK_LEFT: u32 = 0x4000_0050;
+pub const SDLK_DOWN: u32 = 0x4000_0051;
+pub const SDLK_UP: u32 = 0x4000_0052;
+pub const SDL_KMOD_SHIFT: u16 = 0x0003;
+pub const SDL_KMOD_CTRL: u16 = 0x00c0;
 const HOST_DISPLAY_FPS_FALLBACK: f64 = 60.0;
 
+impl SDL_Event {
+    // SDL_KeyboardEvent: key at byte 28, mod at 32, repeat at 37 (offsets include event_type).
+    fn key_down(&self) -> Option<KeyEvent> {
+        if self.event_type != SDL_EVENT_KEY_DOWN {
+            return None;
+        }
+        let p = &self._pad;
+        Some(KeyEvent {
+            keycode: u32::from_ne_bytes([p[24], p[25], p[26], p[27]]),
+            modifiers: u16::from_ne_bytes([p[28], p[29]]),
+            repeat: p[33] != 0,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub keycode: u32,
+    pub modifiers: u16,
+    pub repeat: bool,
+}
+
+impl KeyEvent {
+    pub fn ctrl(&self) -> bool {
+        (self.modifiers & SDL_KMOD_CTRL) != 0
+    }
+
+    // Maps a host key to the code the Apple IIe keyboard would latch at $C000.
+    pub fn apple_ascii(&self) -> Option<u8> {
+        match self.keycode {
+            SDLK_LEFT => Some(0x08),
+            SDLK_RIGHT => Some(0x15),
+            SDLK_UP => Some(0x0b),
+            SDLK_DOWN => Some(0x0a),
+            code @ 0x61..=0x7a if self.ctrl() => Some((code as u8) & 0x1f),
+            code @ 0x61..=0x7a => Some((code as u8).to_ascii_uppercase()),
+            code @ 0x00..=0x7f => Some(code as u8),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SdlDisplayCoreOptions {
     pub title: String,
@@ -106,6 +166,10 @@ pub struct SdlDisplayCoreOptions {
     pub vsync_off: bool,
     pub crossover_vsync_off: bool,
     pub text_base: u16,
+    pub text_addressing: TextAddressing,
+    pub geometry: FrameGeometry,
+    pub hires_color: HiresColor,
+    pub video: VideoBackend,
     pub foreground_color: u32,
 }
 
@@ -120,19 +184,105 @@ impl Default for SdlDisplayCoreOptions {
             vsync_off: false,
             crossover_vsync_off: false,
             text_base: 0x0400,
+            text_addressing: TextAddressing::default(),
+            geometry: FrameGeometry::default(),
+            hires_color: HiresColor::default(),
+            video: VideoBackend::default(),
             foreground_color: COLOR_PHOSPHOR_GREEN,
         }
     }
 }
 
-pub fn run_text_display<Init, Update>(
+// What the host shows after a guest update: the guest frame ending at a CPU
+// cycle, or one solid color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayFrame {
+    Render(u64),
+    Fill(u32),
+}
+
+// `update_bus` receives whole guest frames; master ticks that do not make up
+// a frame carry over to the next host tick.
+pub fn run_text_display<B, Update>(
     options: SdlDisplayCoreOptions,
-    init_ram: Init,
-    mut update_ram: Update,
+    bus: B,
+    mut update_bus: Update,
 ) -> Result<(), String>
 where
-    Init: FnOnce(&mut [u8; 65536]),
-    Update: FnMut(&mut [u8; 65536], usize) -> Option<u32>,
+    B: Bus,
+    Update: FnMut(&mut B, usize) -> Option<u32>,
+{
+    let mut pending_ticks = 0u64;
+    let mut cycle = 0u64;
+    run_display(
+        options,
+        bus,
+        |bus, ticks| {
+            pending_ticks += ticks;
+            let frames = pending_ticks / MASTER_TICKS_PER_FRAME;
+            pending_ticks %= MASTER_TICKS_PER_FRAME;
+            cycle += frames * CYCLES_PER_FRAME;
+            match update_bus(bus, frames as usize) {
+                Some(color) => DisplayFrame::Fill(color),
+                None => DisplayFrame::Render(cycle),
+            }
+        },
+        |_, _| {},
+    )
+}
+
+// Runs a machine on the 14.318 MHz master clock, advanced by the crossover
+// sync once per host frame. Ctrl+F12 acts as Ctrl-Reset; other keys go to
+// the keyboard latch.
+// F11 pauses emulation and opens the monitor on the terminal; hitting a
+// breakpoint does the same. The window is frozen until the monitor quits.
+pub fn run_machine_display(options: SdlDisplayCoreOptions, machine: Machine) -> Result<(), String> {
+    let monitor = RefCell::new(Monitor::new());
+    let enter_monitor = |machine: &mut Machine| {
+        let mut monitor = monitor.borrow_mut();
+        if let Err(err) = monitor.run_repl(machine, io::stdin().lock(), io::stdout()) {
+            eprintln!("monitor: {}", err);
+        }
+        // Whatever ran in the monitor should not be made up in real time.
+        machine.clock.sync_to_cycle(machine.cpu.cycles);
+    };
+
+    run_display(
+        options,
+        machine,
+        |machine, ticks| {
+            let target = machine.clock.advance(ticks);
+            let budget = target.saturating_sub(machine.cpu.cycles);
+            if budget > 0 {
+                let stop = monitor.borrow_mut().run(machine, budget);
+                if let StopReason::Breakpoint(_) = stop {
+                    enter_monitor(machine);
+                }
+            }
+            DisplayFrame::Render(machine.cpu.cycles)
+        },
+        |machine, key| {
+            if key.keycode == SDLK_F11 {
+                enter_monitor(machine);
+            } else if key.keycode == SDLK_F12 && key.ctrl() {
+                machine.reset();
+            } else if let Some(ascii) = key.apple_ascii() {
+                machine.memory.press_key(ascii);
+            }
+        },
+    )
+}
+
+pub fn run_display<B, Update, Key>(
+    options: SdlDisplayCoreOptions,
+    mut bus: B,
+    mut update_bus: Update,
+    mut on_key: Key,
+) -> Result<(), String>
+where
+    B: Bus,
+    Update: FnMut(&mut B, u64) -> DisplayFrame,
+    Key: FnMut(&mut B, KeyEvent),
 {
     let cfg = EchoLabConfig::load_from_path(&options.config_path, options.config_path_explicit)?;
     let title = CString::new(options.title).map_err(|e| e.to_string())?;
@@ -181,12 +331,25 @@ where
             return Err(format!("SDL_SetRenderVSync failed: {}", sdl_error()));
         }
 
+        let backend = match cfg.composite {
+            Some(impairments) => options.video.with_impairments(impairments),
+            None => options.video,
+        };
+        // The window keeps the full-size aspect; other geometries are stretched.
+        let video = TextVideoController::new(options.text_base)
+            .with_text_addressing(options.text_addressing)
+            .with_geometry(options.geometry)
+            .with_hires_color(options.hires_color)
+            .with_backend(backend)
+            .with_foreground_color(options.foreground_color);
+        let (frame_width, frame_height) = video.frame_dimensions();
+
         let texture = SDL_CreateTexture(
             renderer,
             SDL_PIXELFORMAT_ARGB8888,
             SDL_TEXTUREACCESS_STREAMING,
-            FRAME_WIDTH as i32,
-            FRAME_HEIGHT as i32,
+            frame_width as i32,
+            frame_height as i32,
         );
         if texture.is_null() {
             SDL_DestroyRenderer(renderer);
@@ -195,17 +358,13 @@ where
             return Err(format!("SDL_CreateTexture failed: {}", sdl_error()));
         }
 
-        let mut ram = [b' '; 65536];
-        init_ram(&mut ram);
-
-        let video = TextVideoController::new(options.text_base).with_foreground_color(options.foreground_color);
-        let mut frame = ScreenBuffer::new(FRAME_WIDTH, FRAME_HEIGHT);
-        let mut blended_frame = ScreenBuffer::new(FRAME_WIDTH, FRAME_HEIGHT);
+        let mut frame = ScreenBuffer::new(frame_width, frame_height);
+        let mut blended_frame = ScreenBuffer::new(frame_width, frame_height);
         blended_frame.clear(COLOR_BLACK);
         let start = Instant::now();
         let (host_display_fps, mode_fps_known) =
             query_host_display_fps(window).unwrap_or((HOST_DISPLAY_FPS_FALLBACK, false));
-        let mut crossover = CrossoverSync::new(APPLE2E_NTSC_FPS, host_display_fps);
+        let mut crossover = CrossoverSync::new(MASTER_CLOCK_HZ, host_display_fps);
         let mut next_host_deadline = Instant::now();
         let mut last_present_instant: Option<Instant> = None;
 
@@ -218,24 +377,26 @@ where
                 if event.event_type == SDL_EVENT_QUIT {
                     break 'running;
                 }
+                if let Some(key) = event.key_down() {
+                    on_key(&mut bus, key);
+                }
             }
 
-            let guest_steps = if use_crossover_sync {
-                crossover.on_host_tick()
+            let master_ticks = if use_crossover_sync {
+                crossover.on_host_tick() as u64
             } else {
-                1
+                MASTER_TICKS_PER_FRAME
             };
-            let frame_override_color = update_ram(&mut ram, guest_steps);
-
-            if let Some(color) = frame_override_color {
-                frame.clear(color);
-                frame.publish_frame();
-            } else {
-                video.render_frame(&ram, &mut frame);
+            match update_bus(&mut bus, master_ticks) {
+                DisplayFrame::Fill(color) => {
+                    frame.clear(color);
+                    frame.publish_frame();
+                }
+                DisplayFrame::Render(cycle) => video.render_frame(&bus, cycle, &mut frame),
             }
             persistence.apply(frame.pixels(), blended_frame.pixels_mut());
 
-            let pitch = (FRAME_WIDTH * std::mem::size_of::<u32>()) as i32;
+            let pitch = (frame_width * std::mem::size_of::<u32>()) as i32;
             if !SDL_UpdateTexture(
                 texture,
                 ptr::null(),
@@ -0,0 +1,278 @@
+use crate::bus::Bus;
+use crate::cpu::disasm::{self, Instruction};
+use crate::cpu::{Cpu, FLAG_I};
+use crate::machines::Machine;
+use crate::video::VideoSwitches;
+use crate::video::log::VideoLog;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TraceFormat {
+    // Cycle count first, registers as `A=..`, then every bus access of the instruction.
+    #[default]
+    Native,
+    // The Nintendulator/nestest.log column layout most 6502 emulators can emit:
+    // `PC  bytes  disassembly  A:.. X:.. Y:.. P:.. SP:.. CYC:n`, registers and
+    // cycle count taken before the instruction runs.
+    Nestest,
+}
+
+impl TraceFormat {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "native" => Ok(Self::Native),
+            "nestest" => Ok(Self::Nestest),
+            other => Err(format!(
+                "unknown trace format '{}': expected native or nestest",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusAccess {
+    pub addr: u16,
+    pub value: u8,
+    pub write: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceEvent {
+    Instruction(Instruction),
+    Irq,
+    Nmi,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceRecord {
+    pub cycle: u64,
+    pub cpu: Cpu,
+    pub event: TraceEvent,
+    pub accesses: Vec<BusAccess>,
+}
+
+impl TraceRecord {
+    pub fn format(&self, format: TraceFormat) -> String {
+        let (pc, bytes, text) = match self.event {
+            TraceEvent::Instruction(i) => {
+                let bytes: Vec<String> = i.bytes().iter().map(|b| format!("{:02X}", b)).collect();
+                (i.addr, bytes.join(" "), i.to_string())
+            }
+            TraceEvent::Irq => (self.cpu.pc, String::new(), "*IRQ".to_owned()),
+            TraceEvent::Nmi => (self.cpu.pc, String::new(), "*NMI".to_owned()),
+        };
+        let cpu = &self.cpu;
+
+        match format {
+            TraceFormat::Native => {
+                let mut line = format!(
+                    "{:>10}  {:04X}  {:<8}  {:<14}  A={:02X} X={:02X} Y={:02X} P={:02X} SP={:02X}",
+                    self.cycle, pc, bytes, text, cpu.a, cpu.x, cpu.y, cpu.p, cpu.sp
+                );
+                if !self.accesses.is_empty() {
+                    line.push(' ');
+                }
+                for access in &self.accesses {
+                    let kind = if access.write { 'w' } else { 'r' };
+                    let _ = write!(line, " {}{:04X}:{:02X}", kind, access.addr, access.value);
+                }
+                line
+            }
+            TraceFormat::Nestest => format!(
+                "{:04X}  {:<8}  {:<32}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} CYC:{}",
+                pc, bytes, text, cpu.a, cpu.x, cpu.y, cpu.p, cpu.sp, self.cycle
+            ),
+        }
+    }
+}
+
+pub enum TraceSink {
+    Memory(Vec<String>),
+    File(BufWriter<File>),
+}
+
+pub struct Tracer {
+    format: TraceFormat,
+    sink: TraceSink,
+}
+
+impl Tracer {
+    pub fn in_memory(format: TraceFormat) -> Self {
+        Self {
+            format,
+            sink: TraceSink::Memory(Vec::new()),
+        }
+    }
+
+    pub fn to_file(path: &str, format: TraceFormat) -> Result<Self, String> {
+        let file =
+            File::create(path).map_err(|e| format!("failed to create trace '{}': {}", path, e))?;
+        Ok(Self {
+            format,
+            sink: TraceSink::File(BufWriter::new(file)),
+        })
+    }
+
+    pub fn format(&self) -> TraceFormat {
+        self.format
+    }
+
+    // Lines collected by an in-memory tracer; empty for a file sink.
+    pub fn lines(&self) -> &[String] {
+        match &self.sink {
+            TraceSink::Memory(lines) => lines.as_slice(),
+            TraceSink::File(_) => &[],
+        }
+    }
+
+    pub fn step(&mut self, machine: &mut Machine) -> Result<u32, String> {
+        machine.dispatch_events();
+        self.step_cpu(&mut machine.cpu, &mut machine.memory)
+    }
+
+    pub fn step_cpu<B: Bus + ?Sized>(&mut self, cpu: &mut Cpu, bus: &mut B) -> Result<u32, String> {
+        let (record, cycles) = trace_step(cpu, bus);
+        self.record(&record)?;
+        Ok(cycles)
+    }
+
+    pub fn run_cycles(&mut self, machine: &mut Machine, cycles: u64) -> Result<u64, String> {
+        let start = machine.cpu.cycles;
+        let target = start.saturating_add(cycles);
+        while machine.cpu.cycles < target {
+            self.step(machine)?;
+        }
+        Ok(machine.cpu.cycles - start)
+    }
+
+    pub fn record(&mut self, record: &TraceRecord) -> Result<(), String> {
+        let line = record.format(self.format);
+        match &mut self.sink {
+            TraceSink::Memory(lines) => lines.push(line),
+            TraceSink::File(file) => {
+                writeln!(file, "{}", line).map_err(|e| format!("failed to write trace: {}", e))?
+            }
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<(), String> {
+        match self.sink {
+            TraceSink::Memory(_) => Ok(()),
+            TraceSink::File(mut file) => file
+                .flush()
+                .map_err(|e| format!("failed to write trace: {}", e)),
+        }
+    }
+}
+
+// Runs one CPU step and returns what it did. The record holds the registers
+// as they were before the step, which is what reference traces print.
+pub fn trace_step<B: Bus + ?Sized>(cpu: &mut Cpu, bus: &mut B) -> (TraceRecord, u32) {
+    let before = cpu.clone();
+    let event = if cpu.nmi_pending() {
+        TraceEvent::Nmi
+    } else if cpu.irq_asserted() && !cpu.flag(FLAG_I) {
+        TraceEvent::Irq
+    } else {
+        TraceEvent::Instruction(disasm::disassemble_for(cpu.variant(), bus, cpu.pc))
+    };
+
+    let mut recorder = RecordingBus {
+        inner: bus,
+        accesses: Vec::new(),
+    };
+    let cycles = cpu.step(&mut recorder);
+    let record = TraceRecord {
+        cycle: before.cycles,
+        cpu: before,
+        event,
+        accesses: recorder.accesses,
+    };
+    (record, cycles)
+}
+
+struct RecordingBus<'a, B: Bus + ?Sized> {
+    inner: &'a mut B,
+    accesses: Vec<BusAccess>,
+}
+
+impl<B: Bus + ?Sized> Bus for RecordingBus<'_, B> {
+    fn read(&mut self, addr: u16, cycle: u64) -> u8 {
+        let value = self.inner.read(addr, cycle);
+        self.accesses.push(BusAccess {
+            addr,
+            value,
+            write: false,
+        });
+        value
+    }
+
+    fn write(&mut self, addr: u16, value: u8, cycle: u64) {
+        self.inner.write(addr, value, cycle);
+        self.accesses.push(BusAccess {
+            addr,
+            value,
+            write: true,
+        });
+    }
+
+    fn peek(&self, addr: u16) -> u8 {
+        self.inner.peek(addr)
+    }
+
+    fn peek_display(&self, addr: u16, aux: bool) -> u8 {
+        self.inner.peek_display(addr, aux)
+    }
+
+    fn video_switches(&self) -> Option<VideoSwitches> {
+        self.inner.video_switches()
+    }
+
+    fn video_log(&self) -> Option<&VideoLog> {
+        self.inner.video_log()
+    }
+}
+
+// Compares a trace against a golden copy. On mismatch, reports the first
+// divergent line with up to `context` matching lines before it.
+pub fn diff_traces(expected: &str, actual: &[String], context: usize) -> Result<(), String> {
+    let expected: Vec<&str> = expected.lines().collect();
+    let Some(index) = (0..expected.len().max(actual.len()))
+        .find(|&i| expected.get(i).copied() != actual.get(i).map(String::as_str))
+    else {
+        return Ok(());
+    };
+
+    let mut report = format!(
+        "trace diverges at line {} (expected {} lines, got {})\n",
+        index + 1,
+        expected.len(),
+        actual.len()
+    );
+    for (i, line) in expected
+        .iter()
+        .enumerate()
+        .take(index)
+        .skip(index.saturating_sub(context))
+    {
+        let _ = writeln!(report, "  {:>6}  {}", i + 1, line);
+    }
+    let missing = "<end of trace>";
+    let _ = writeln!(
+        report,
+        "- {:>6}  {}",
+        index + 1,
+        expected.get(index).copied().unwrap_or(missing)
+    );
+    let _ = write!(
+        report,
+        "+ {:>6}  {}",
+        index + 1,
+        actual.get(index).map(String::as_str).unwrap_or(missing)
+    );
+    Err(report)
+}
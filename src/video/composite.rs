@@ -0,0 +1,279 @@
+use super::COLOR_BLACK;
+use super::scanner::VISIBLE_LINES;
+use crate::rng::FastRng;
+use crate::scheduler::MASTER_TICKS_PER_SCANLINE;
+use std::f32::consts::FRAC_PI_2;
+
+// The master clock is exactly four times the color subcarrier, so the line is
+// sampled once per 14M dot and every sample sits 90 degrees further along the
+// carrier than the last.
+pub const SAMPLES_PER_LINE: usize = MASTER_TICKS_PER_SCANLINE as usize;
+pub const SYNC_END: usize = 66;
+pub const BURST_START: usize = 76;
+pub const BURST_END: usize = BURST_START + 36;
+// A multiple of four, so a dot's place in the color cycle is its x mod 4.
+pub const ACTIVE_START: usize = 228;
+
+// Levels in IRE.
+pub const SYNC_LEVEL: f32 = -40.0;
+pub const BLANK_LEVEL: f32 = 0.0;
+pub const WHITE_LEVEL: f32 = 100.0;
+pub const BURST_AMPLITUDE: f32 = 20.0;
+// Where the IIe's burst falls against the dot clock: a lone dot in the first
+// phase of the cycle decodes as lo-res magenta.
+pub const BURST_PHASE: f32 = -93.0 * DEGREE;
+
+// NTSC puts burst on -U, 57 degrees behind the I axis.
+const BURST_TO_I: f32 = 57.0 * DEGREE;
+const DEGREE: f32 = std::f32::consts::PI / 180.0;
+
+pub fn carrier_phase(sample: usize) -> f32 {
+    (sample % 4) as f32 * FRAC_PI_2
+}
+
+// Sync, blanking, burst (when the display is in a color mode) and the dots as
+// black or white levels. Color lives only in the dot pattern's subcarrier
+// content.
+pub fn encode_line(dots: &[bool], burst: bool) -> Vec<f32> {
+    let mut samples = vec![BLANK_LEVEL; SAMPLES_PER_LINE];
+    samples[..SYNC_END].fill(SYNC_LEVEL);
+    if burst {
+        for (n, sample) in samples
+            .iter_mut()
+            .enumerate()
+            .take(BURST_END)
+            .skip(BURST_START)
+        {
+            *sample = BURST_AMPLITUDE * (carrier_phase(n) - BURST_PHASE).cos();
+        }
+    }
+    for (sample, on) in samples[ACTIVE_START..].iter_mut().zip(dots) {
+        if *on {
+            *sample = WHITE_LEVEL;
+        }
+    }
+    samples
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Burst {
+    pub amplitude: f32,
+    pub phase: f32,
+}
+
+pub fn measure_burst(samples: &[f32]) -> Burst {
+    let (mut re, mut im) = (0.0f32, 0.0f32);
+    for (n, sample) in samples.iter().enumerate().take(BURST_END).skip(BURST_START) {
+        re += sample * carrier_phase(n).cos();
+        im += sample * carrier_phase(n).sin();
+    }
+    let scale = 2.0 / (BURST_END - BURST_START) as f32;
+    Burst {
+        amplitude: (re * re + im * im).sqrt() * scale,
+        phase: im.atan2(re),
+    }
+}
+
+// Box low-pass lengths in samples. Four samples is one carrier cycle, which
+// notches the subcarrier out of luma; the chroma lengths set how far color
+// smears past an edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct YiqFilter {
+    pub luma: usize,
+    pub i: usize,
+    pub q: usize,
+}
+
+impl Default for YiqFilter {
+    fn default() -> Self {
+        Self {
+            luma: 4,
+            i: 8,
+            q: 12,
+        }
+    }
+}
+
+// What the signal picks up between the machine and the monitor. Everything is
+// off by default. Noise and jitter come from a FastRng seeded per line, so a
+// given seed, frame and line always look the same.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Impairments {
+    pub seed: u64,
+    // Peak RF-modulator noise in IRE.
+    pub noise: f32,
+    // Extra box low-pass on luma, in samples; 0 or 1 leaves it alone.
+    pub luma_bandwidth: usize,
+    // A reflected copy of the signal `ghost_delay` samples late.
+    pub ghost_delay: usize,
+    pub ghost_gain: f32,
+    // Decoder hue error in degrees.
+    pub chroma_phase: f32,
+    // Largest horizontal shift of a line, in samples.
+    pub jitter: usize,
+}
+
+impl Impairments {
+    pub fn apply(&self, samples: &mut [f32], frame: u64, line: u16) {
+        let index = frame * VISIBLE_LINES as u64 + line as u64;
+        let mut rng = FastRng::new(FastRng::new(self.seed).next_u64() ^ index);
+
+        // A line can never move by its whole length.
+        let jitter = self.jitter.min(samples.len().saturating_sub(1));
+        if jitter > 0 {
+            let span = 2 * jitter as u64 + 1;
+            let shift = (rng.next_u64() % span) as usize;
+            if shift > jitter {
+                let shift = shift - jitter;
+                samples.copy_within(..samples.len() - shift, shift);
+                samples[..shift].fill(BLANK_LEVEL);
+            } else {
+                let shift = jitter - shift;
+                samples.copy_within(shift.., 0);
+                let len = samples.len();
+                samples[len - shift..].fill(BLANK_LEVEL);
+            }
+        }
+
+        if self.ghost_delay > 0 && self.ghost_gain != 0.0 {
+            for n in (self.ghost_delay..samples.len()).rev() {
+                samples[n] += self.ghost_gain * samples[n - self.ghost_delay];
+            }
+        }
+
+        if self.noise > 0.0 {
+            for sample in samples.iter_mut() {
+                let unit = rng.next_u16() as f32 / u16::MAX as f32;
+                *sample += self.noise * (2.0 * unit - 1.0);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompositeDecoder {
+    pub filter: YiqFilter,
+    pub saturation: f32,
+    pub impairments: Impairments,
+}
+
+impl Default for CompositeDecoder {
+    fn default() -> Self {
+        Self {
+            filter: YiqFilter::default(),
+            saturation: 0.5,
+            impairments: Impairments::default(),
+        }
+    }
+}
+
+impl CompositeDecoder {
+    pub fn with_filter(mut self, filter: YiqFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_saturation(mut self, saturation: f32) -> Self {
+        self.saturation = saturation;
+        self
+    }
+
+    pub fn with_impairments(mut self, impairments: Impairments) -> Self {
+        self.impairments = impairments;
+        self
+    }
+
+    // Decodes the active part of a line into `out`, one pixel per sample. A
+    // line without burst trips the color killer and shows luma without the
+    // subcarrier notch.
+    pub fn decode_line(&self, samples: &[f32], out: &mut [u32]) {
+        let bandwidth = self.impairments.luma_bandwidth;
+        let burst = measure_burst(samples);
+        if burst.amplitude < BURST_AMPLITUDE / 2.0 {
+            let luma = box_filter(samples, bandwidth);
+            for (pixel, sample) in out.iter_mut().zip(&luma[ACTIVE_START..]) {
+                *pixel = yiq_to_rgb(sample / WHITE_LEVEL, 0.0, 0.0);
+            }
+            return;
+        }
+
+        let luma = box_filter(samples, self.filter.luma);
+        let reference = burst.phase + BURST_TO_I + self.impairments.chroma_phase * DEGREE;
+        // Automatic color control: chroma is scaled against the burst it came with.
+        let gain = self.saturation * BURST_AMPLITUDE / burst.amplitude / WHITE_LEVEL;
+        let (mut i, mut q) = (vec![0.0; samples.len()], vec![0.0; samples.len()]);
+        for (n, sample) in samples.iter().enumerate() {
+            let chroma = 2.0 * (sample - luma[n]);
+            let phase = carrier_phase(n) - reference;
+            i[n] = chroma * phase.cos();
+            q[n] = chroma * phase.sin();
+        }
+        let i = box_filter(&i, self.filter.i);
+        let q = box_filter(&q, self.filter.q);
+        let luma = box_filter(&luma, bandwidth);
+
+        for (x, pixel) in out.iter_mut().enumerate() {
+            let n = ACTIVE_START + x;
+            if n >= samples.len() {
+                *pixel = COLOR_BLACK;
+                continue;
+            }
+            *pixel = yiq_to_rgb(luma[n] / WHITE_LEVEL, i[n] * gain, q[n] * gain);
+        }
+    }
+}
+
+// Centered moving average; samples past either end of the line count as blank.
+fn box_filter(input: &[f32], len: usize) -> Vec<f32> {
+    let len = len.max(1);
+    let mut prefix = Vec::with_capacity(input.len() + 1);
+    prefix.push(0.0f32);
+    for sample in input {
+        prefix.push(prefix.last().copied().unwrap_or(0.0) + sample);
+    }
+    (0..input.len())
+        .map(|n| {
+            let start = n.saturating_sub(len / 2);
+            let end = (n + len - len / 2).min(input.len());
+            (prefix[end] - prefix[start]) / len as f32
+        })
+        .collect()
+}
+
+fn yiq_to_rgb(y: f32, i: f32, q: f32) -> u32 {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u32;
+    let r = channel(y + 0.956 * i + 0.621 * q);
+    let g = channel(y - 0.272 * i - 0.647 * q);
+    let b = channel(y - 1.106 * i + 1.703 * q);
+    0xff00_0000 | (r << 16) | (g << 8) | b
+}
+
+// Digital draws each mode from its own color rules; Composite runs every line
+// through an NTSC encode and decode, as a color monitor would see it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum VideoBackend {
+    #[default]
+    Digital,
+    Composite(CompositeDecoder),
+}
+
+impl VideoBackend {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "digital" => Ok(Self::Digital),
+            "composite" => Ok(Self::Composite(CompositeDecoder::default())),
+            other => Err(format!(
+                "unknown video backend '{}': expected digital or composite",
+                other
+            )),
+        }
+    }
+
+    // Digital output has no signal to impair.
+    pub fn with_impairments(self, impairments: Impairments) -> Self {
+        match self {
+            Self::Digital => Self::Digital,
+            Self::Composite(decoder) => Self::Composite(decoder.with_impairments(impairments)),
+        }
+    }
+}
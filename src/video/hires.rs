@@ -0,0 +1,119 @@
+use super::{COLOR_BLACK, LORES_PALETTE};
+
+// Hi-res artifact colors named by the lo-res entries with the same dot pattern.
+pub const HIRES_VIOLET: u32 = LORES_PALETTE[0x3];
+pub const HIRES_GREEN: u32 = LORES_PALETTE[0xc];
+pub const HIRES_BLUE: u32 = LORES_PALETTE[0x6];
+pub const HIRES_ORANGE: u32 = LORES_PALETTE[0x9];
+pub const HIRES_WHITE: u32 = LORES_PALETTE[0xf];
+
+pub const HIRES_COLS: usize = 280;
+
+// Artifact reads colors off the 560-dot stream the way a composite color
+// monitor would; Idealized is the textbook six colors per 280-pixel dot (and
+// 16 colors per 4-dot group in double hi-res); Monochrome shows the dots in
+// the text foreground color.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HiresColor {
+    #[default]
+    Artifact,
+    Idealized,
+    Monochrome,
+}
+
+impl HiresColor {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "artifact" => Ok(Self::Artifact),
+            "ideal" | "idealized" => Ok(Self::Idealized),
+            "mono" | "monochrome" => Ok(Self::Monochrome),
+            other => Err(format!(
+                "unknown hi-res color model '{}': expected artifact, ideal or mono",
+                other
+            )),
+        }
+    }
+}
+
+// Each 8-line group is $400 apart, each 64-line third $28 apart, within the
+// $2000 (page 1) or $4000 (page 2) page.
+pub fn row_address(base: u16, line: u16) -> u16 {
+    let line = line as usize;
+    let offset = 0x400 * (line % 8) + 0x80 * ((line / 8) % 8) + 0x28 * (line / 64);
+    base.wrapping_add(offset as u16)
+}
+
+// Shifts one byte's seven dots, two 14M dots each, into the line at `x0`. A set
+// palette bit delays them by one 14M dot, and the first half-dot then repeats
+// the previous byte's last dot. Returns this byte's last dot.
+pub fn shift_byte(byte: u8, x0: usize, previous: bool, dots: &mut [bool]) -> bool {
+    let delayed = byte & 0x80 != 0;
+    let start = x0 + delayed as usize;
+    if delayed && let Some(dot) = dots.get_mut(x0) {
+        *dot = previous;
+    }
+    for bit in 0..7 {
+        let on = (byte >> bit) & 1 != 0;
+        for half in 0..2 {
+            if let Some(dot) = dots.get_mut(start + bit * 2 + half) {
+                *dot = on;
+            }
+        }
+    }
+    (byte >> 6) & 1 != 0
+}
+
+// Double hi-res shifts the aux byte then the main byte at one 14M dot per bit;
+// palette bits are ignored. Returns the last dot.
+pub fn shift_double(aux: u8, main: u8, x0: usize, dots: &mut [bool]) -> bool {
+    for (half, byte) in [aux, main].into_iter().enumerate() {
+        for bit in 0..7 {
+            if let Some(dot) = dots.get_mut(x0 + half * 7 + bit) {
+                *dot = (byte >> bit) & 1 != 0;
+            }
+        }
+    }
+    (main >> 6) & 1 != 0
+}
+
+// The color at dot `x`: the four dots around it form a lo-res pattern whose
+// bits sit at their position in the 4-dot color cycle.
+pub fn artifact_color(dots: &[bool], x: usize) -> u32 {
+    let start = x.saturating_sub(1);
+    let window = dots.iter().enumerate().skip(start).take(x + 3 - start);
+    let pattern = window
+        .filter(|(_, on)| **on)
+        .fold(0usize, |pattern, (dx, _)| pattern | 1 << (dx % 4));
+    LORES_PALETTE[pattern]
+}
+
+// The RGB-style double hi-res color: one lo-res pattern per 4-dot group,
+// giving 140 pixels of 16 colors.
+pub fn aligned_color(dots: &[bool], x: usize) -> u32 {
+    let start = x - x % 4;
+    let pattern = dots[start..(start + 4).min(dots.len())]
+        .iter()
+        .enumerate()
+        .filter(|(_, on)| **on)
+        .fold(0usize, |pattern, (bit, _)| pattern | 1 << bit);
+    LORES_PALETTE[pattern]
+}
+
+// Six-color rendering of one 280-pixel line: neighboring lit pixels are white,
+// lone ones take the color of their column parity and palette bit.
+pub fn idealized_color(pixels: &[bool; HIRES_COLS], palette: &[bool], x: usize) -> u32 {
+    if !pixels[x] {
+        return COLOR_BLACK;
+    }
+    let left = x > 0 && pixels[x - 1];
+    let right = x + 1 < HIRES_COLS && pixels[x + 1];
+    if left || right {
+        return HIRES_WHITE;
+    }
+    match (palette[x / 7], x % 2 == 1) {
+        (false, false) => HIRES_VIOLET,
+        (false, true) => HIRES_GREEN,
+        (true, false) => HIRES_BLUE,
+        (true, true) => HIRES_ORANGE,
+    }
+}
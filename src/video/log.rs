@@ -0,0 +1,149 @@
+use super::VideoSwitches;
+use super::scanner::CYCLES_PER_FRAME;
+use crate::bus::Bus;
+use std::collections::VecDeque;
+use std::ops::Range;
+
+// RAM the display hardware can fetch from: text/lo-res pages 1-2 and hi-res
+// pages 1-2, in both banks.
+pub const DISPLAY_RANGES: [Range<u16>; 2] = [0x0400..0x0c00, 0x2000..0x6000];
+
+pub fn is_display_addr(addr: u16) -> bool {
+    DISPLAY_RANGES.iter().any(|range| range.contains(&addr))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoEvent {
+    Switches {
+        old: VideoSwitches,
+        new: VideoSwitches,
+    },
+    Write {
+        addr: u16,
+        aux: bool,
+        old: u8,
+        new: u8,
+    },
+}
+
+// Cycle-stamped changes to display memory and video switches over the last
+// frame's worth of cycles, so a renderer can replay what the beam saw.
+#[derive(Debug, Clone, Default)]
+pub struct VideoLog {
+    now: u64,
+    events: VecDeque<(u64, VideoEvent)>,
+}
+
+impl VideoLog {
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    pub fn events(&self) -> impl Iterator<Item = &(u64, VideoEvent)> {
+        self.events.iter()
+    }
+
+    // Moves time forward and forgets events the beam has since passed again.
+    pub fn advance(&mut self, cycle: u64) {
+        self.now = self.now.max(cycle);
+        let oldest = self.window_start();
+        while self.events.front().is_some_and(|(at, _)| *at < oldest) {
+            self.events.pop_front();
+        }
+    }
+
+    pub fn record(&mut self, cycle: u64, event: VideoEvent) {
+        self.advance(cycle);
+        self.events.push_back((cycle.max(self.now), event));
+    }
+
+    pub fn clear(&mut self) {
+        self.now = 0;
+        self.events.clear();
+    }
+
+    // First cycle of the frame-long window ending with `now`.
+    pub fn window_start(&self) -> u64 {
+        (self.now + 1).saturating_sub(CYCLES_PER_FRAME)
+    }
+
+    // Rebuilds display memory and switches as the beam saw them at the start
+    // of the frame-long window ending at `end`, from the bus's current state.
+    // Events after `end` are undone too, and only replayed if asked for; events
+    // the log has already dropped cannot be.
+    pub fn replay<B: Bus + ?Sized>(
+        &self,
+        bus: &B,
+        switches: VideoSwitches,
+        end: u64,
+    ) -> VideoReplay<'_> {
+        let mut banks = [Box::new([0u8; 65536]), Box::new([0u8; 65536])];
+        for (index, bank) in banks.iter_mut().enumerate() {
+            for range in DISPLAY_RANGES {
+                for addr in range {
+                    bank[addr as usize] = bus.peek_display(addr, index == 1);
+                }
+            }
+        }
+
+        let mut replay = VideoReplay {
+            log: self,
+            banks,
+            switches,
+            next: self.events.len(),
+        };
+        let start = (end + 1).saturating_sub(CYCLES_PER_FRAME);
+        while replay.next > 0 && self.events[replay.next - 1].0 >= start {
+            replay.next -= 1;
+            replay.undo(self.events[replay.next].1);
+        }
+        replay
+    }
+}
+
+pub struct VideoReplay<'a> {
+    log: &'a VideoLog,
+    banks: [Box<[u8; 65536]>; 2],
+    switches: VideoSwitches,
+    next: usize,
+}
+
+impl VideoReplay<'_> {
+    // Applies every event before `cycle`: the video fetch happens in the first
+    // half of a cycle, ahead of the CPU's access.
+    pub fn advance_to(&mut self, cycle: u64) {
+        while let Some(&(at, event)) = self.log.events.get(self.next) {
+            if at >= cycle {
+                break;
+            }
+            self.apply(event);
+            self.next += 1;
+        }
+    }
+
+    pub fn switches(&self) -> VideoSwitches {
+        self.switches
+    }
+
+    pub fn peek(&self, addr: u16, aux: bool) -> u8 {
+        self.banks[aux as usize][addr as usize]
+    }
+
+    fn apply(&mut self, event: VideoEvent) {
+        match event {
+            VideoEvent::Switches { new, .. } => self.switches = new,
+            VideoEvent::Write { addr, aux, new, .. } => {
+                self.banks[aux as usize][addr as usize] = new
+            }
+        }
+    }
+
+    fn undo(&mut self, event: VideoEvent) {
+        match event {
+            VideoEvent::Switches { old, .. } => self.switches = old,
+            VideoEvent::Write { addr, aux, old, .. } => {
+                self.banks[aux as usize][addr as usize] = old
+            }
+        }
+    }
+}
@@ -1,4 +1,15 @@
+pub mod composite;
+pub mod hires;
+pub mod log;
+pub mod scanner;
+
+use crate::bus::Bus;
 use crate::screen_buffer::ScreenBuffer;
+use composite::VideoBackend;
+use hires::HiresColor;
+use log::VideoLog;
+use scanner::{BeamPosition, CYCLES_PER_FRAME, VISIBLE_LINES};
+use std::ops::Range;
 
 pub const TEXT_COLS: usize = 40;
 pub const TEXT_ROWS: usize = 24;
@@ -6,20 +17,162 @@ pub const GLYPH_WIDTH: usize = 7;
 pub const CELL_WIDTH: usize = GLYPH_WIDTH * 2;
 pub const GLYPH_HEIGHT: usize = 8;
 pub const CELL_HEIGHT: usize = GLYPH_HEIGHT * 2;
-pub const FRAME_WIDTH: usize = TEXT_COLS * CELL_WIDTH;
+// One pixel per 14M dot: 80 columns of 7 dots, or 40 columns doubled.
+pub const FRAME_WIDTH: usize = 80 * GLYPH_WIDTH;
 pub const FRAME_HEIGHT: usize = TEXT_ROWS * CELL_HEIGHT;
 
 pub const COLOR_BLACK: u32 = 0xff00_0000;
 pub const COLOR_PHOSPHOR_GREEN: u32 = 0xff00_ff00;
 pub const COLOR_WHITE: u32 = 0xffff_ffff;
 
+// Lo-res colors by nibble, as RGB values documented for the IIgs.
+pub const LORES_PALETTE: [u32; 16] = [
+    0xff00_0000, // black
+    0xffdd_0033, // magenta
+    0xff00_0099, // dark blue
+    0xffdd_22dd, // purple
+    0xff00_7722, // dark green
+    0xff55_5555, // grey 1
+    0xff22_22ff, // medium blue
+    0xff66_aaff, // light blue
+    0xff88_5500, // brown
+    0xffff_6600, // orange
+    0xffaa_aaaa, // grey 2
+    0xffff_9988, // pink
+    0xff11_dd00, // light green
+    0xffff_ff00, // yellow
+    0xff44_ff99, // aquamarine
+    0xffff_ffff, // white
+];
+
+// Mixed mode shows text from the first line of row 20 down.
+pub const MIXED_TEXT_LINE: u16 = 160;
+
 const TEXT_DISPLAY_ROM: &[u8; 6144] = include_bytes!("../../assets/roms/retro_7x8_mono.bin");
 const TEXT_DISPLAY_BANK_SIZE: usize = 2048;
+// Bank 0 is a plain set ($00-$7F normal, $80-$FF inverse) for buses without
+// IIe video switches; banks 1 and 2 are the IIe primary and alternate sets.
 const NORMAL_BANK_OFFSET: usize = 0;
+const PRIMARY_BANK_OFFSET: usize = TEXT_DISPLAY_BANK_SIZE;
+const ALTERNATE_BANK_OFFSET: usize = TEXT_DISPLAY_BANK_SIZE * 2;
+
+// The IOU toggles FLASH every 16 frames, just under 2 Hz.
+pub const FLASH_FRAMES: u64 = 16;
+
+pub fn flash_inverted(cycle: u64) -> bool {
+    (cycle / CYCLES_PER_FRAME / FLASH_FRAMES) % 2 == 1
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VideoSwitches {
+    pub text: bool,
+    pub mixed: bool,
+    pub page2: bool,
+    pub hires: bool,
+    pub col80: bool,
+    pub store80: bool,
+    pub altcharset: bool,
+    pub dhires: bool,
+}
+
+impl VideoSwitches {
+    // With 80STORE on, PAGE2 banks aux memory for the CPU instead of flipping the display page.
+    pub fn display_page2(&self) -> bool {
+        self.page2 && !self.store80
+    }
+
+    pub fn text_at(&self, line: u16) -> bool {
+        self.text || (self.mixed && line >= MIXED_TEXT_LINE)
+    }
+
+    pub fn mode_at(&self, line: u16) -> DisplayMode {
+        let double = self.col80 && self.dhires;
+        match (self.text_at(line), self.hires, double) {
+            (true, _, _) => DisplayMode::Text,
+            (false, true, false) => DisplayMode::HiRes,
+            (false, true, true) => DisplayMode::DoubleHiRes,
+            (false, false, false) => DisplayMode::LoRes,
+            (false, false, true) => DisplayMode::DoubleLoRes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayMode {
+    Text,
+    LoRes,
+    HiRes,
+    DoubleLoRes,
+    DoubleHiRes,
+}
+
+// Interleaved is the IIe's row layout: each 128-byte group holds three rows
+// ($400, $428, $450) plus eight unseen screen-hole bytes. Linear packs rows
+// back to back for hand-built demo pages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextAddressing {
+    #[default]
+    Interleaved,
+    Linear,
+}
+
+impl TextAddressing {
+    pub fn row_address(self, base: u16, row: usize) -> u16 {
+        let offset = match self {
+            Self::Interleaved => 0x80 * (row % 8) + 0x28 * (row / 8),
+            Self::Linear => row * TEXT_COLS,
+        };
+        base.wrapping_add(offset as u16)
+    }
+}
+
+// How the 192 visible scanlines map onto output rows. Scanlines leaves every
+// other row black, LineDoubled repeats each line, Native is one row per line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FrameGeometry {
+    #[default]
+    Scanlines,
+    LineDoubled,
+    Native,
+}
+
+impl FrameGeometry {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "scanlines" => Ok(Self::Scanlines),
+            "doubled" => Ok(Self::LineDoubled),
+            "native" => Ok(Self::Native),
+            other => Err(format!(
+                "unknown geometry '{}': expected scanlines, doubled or native",
+                other
+            )),
+        }
+    }
+
+    pub fn dimensions(self) -> (usize, usize) {
+        match self {
+            Self::Scanlines | Self::LineDoubled => (FRAME_WIDTH, FRAME_HEIGHT),
+            Self::Native => (FRAME_WIDTH, FRAME_HEIGHT / 2),
+        }
+    }
+
+    pub fn output_rows(self, line: u16) -> Range<usize> {
+        let line = line as usize;
+        match self {
+            Self::Scanlines => line * 2..line * 2 + 1,
+            Self::LineDoubled => line * 2..line * 2 + 2,
+            Self::Native => line..line + 1,
+        }
+    }
+}
 
 pub struct TextVideoController {
     text_base: u16,
     foreground_color: u32,
+    addressing: TextAddressing,
+    geometry: FrameGeometry,
+    hires_color: HiresColor,
+    backend: VideoBackend,
 }
 
 impl Default for TextVideoController {
@@ -33,58 +186,350 @@ impl TextVideoController {
         Self {
             text_base,
             foreground_color: COLOR_PHOSPHOR_GREEN,
+            addressing: TextAddressing::default(),
+            geometry: FrameGeometry::default(),
+            hires_color: HiresColor::default(),
+            backend: VideoBackend::default(),
         }
     }
 
+    pub fn with_text_addressing(mut self, addressing: TextAddressing) -> Self {
+        self.addressing = addressing;
+        self
+    }
+
     pub fn with_foreground_color(mut self, color: u32) -> Self {
         self.foreground_color = color;
         self
     }
 
+    pub fn with_geometry(mut self, geometry: FrameGeometry) -> Self {
+        self.geometry = geometry;
+        self
+    }
+
+    pub fn with_hires_color(mut self, hires_color: HiresColor) -> Self {
+        self.hires_color = hires_color;
+        self
+    }
+
+    pub fn with_backend(mut self, backend: VideoBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
     pub fn frame_dimensions(&self) -> (usize, usize) {
-        (FRAME_WIDTH, FRAME_HEIGHT)
+        self.geometry.dimensions()
     }
 
-    pub fn render_frame(&self, ram: &[u8; 65536], out: &mut ScreenBuffer) {
-        assert_eq!(out.dimensions(), (FRAME_WIDTH, FRAME_HEIGHT));
+    // Buses that keep a video log get what the beam drew over the frame's worth
+    // of cycles ending at `cycle`, so mid-frame switch changes and writes show
+    // up where they happened; others render a snapshot of current memory.
+    pub fn render_frame<B: Bus + ?Sized>(&self, bus: &B, cycle: u64, out: &mut ScreenBuffer) {
+        assert_eq!(out.dimensions(), self.frame_dimensions());
 
         out.clear(COLOR_BLACK);
 
-        for row in 0..TEXT_ROWS {
-            for col in 0..TEXT_COLS {
-                let char_addr = self.text_base as usize + row * TEXT_COLS + col;
-                let ch = ram[char_addr & 0xffff];
-                self.render_cell(ch, col, row, out);
+        let fetches = match (bus.video_switches(), bus.video_log()) {
+            (Some(switches), Some(log)) => self.fetch_scanlines(bus, switches, log, cycle),
+            (switches, _) => {
+                let mut fetches = vec![[ColumnFetch::default(); TEXT_COLS]; VISIBLE_LINES as usize];
+                for (line, columns) in fetches.iter_mut().enumerate() {
+                    for (column, fetch) in columns.iter_mut().enumerate() {
+                        let position = BeamPosition {
+                            line: line as u16,
+                            column: column as u8,
+                        };
+                        *fetch = self.fetch(
+                            switches.as_ref(),
+                            flash_inverted(cycle),
+                            position,
+                            |addr, aux| bus.peek_display(addr, aux),
+                        );
+                    }
+                }
+                fetches
             }
+        };
+
+        // Composite impairments are seeded per frame, so a frame re-renders the same.
+        let frame = cycle / CYCLES_PER_FRAME;
+        for (line, columns) in fetches.iter().enumerate() {
+            self.render_line(line as u16, frame, columns, out);
         }
 
         out.publish_frame();
     }
 
-    fn render_cell(&self, ch: u8, col: usize, row: usize, out: &mut ScreenBuffer) {
-        let x0 = col * CELL_WIDTH;
-        let y0 = row * CELL_HEIGHT;
-        let code = ch as usize;
-        let glyph_base = NORMAL_BANK_OFFSET + code * GLYPH_HEIGHT;
-
-        debug_assert!(glyph_base + GLYPH_HEIGHT <= TEXT_DISPLAY_BANK_SIZE);
-
-        for glyph_y in 0..GLYPH_HEIGHT {
-            let row_bits = TEXT_DISPLAY_ROM[glyph_base + glyph_y] & 0x7f;
-            let py = y0 + glyph_y * 2;
-
-            for x in 0..CELL_WIDTH {
-                let px = x0 + x;
-                let glyph_x = x / 2;
-                // Apple IIe glyph rows in this ROM table are stored LSB-left for 7-bit pixels.
-                let glyph_on = ((row_bits >> glyph_x) & 0x01) != 0;
-                let color = if glyph_on {
-                    self.foreground_color
-                } else {
-                    COLOR_BLACK
-                };
-                let _ = out.set_pixel(px, py, color);
+    // Walks the frame-long window ending at `render_cycle` in beam order,
+    // replaying logged events up to each visible column's fetch. Columns the
+    // beam has not reached since power-on show the state at `render_cycle`.
+    fn fetch_scanlines<B: Bus + ?Sized>(
+        &self,
+        bus: &B,
+        switches: VideoSwitches,
+        log: &VideoLog,
+        render_cycle: u64,
+    ) -> Vec<[ColumnFetch; TEXT_COLS]> {
+        let mut fetches = vec![[ColumnFetch::default(); TEXT_COLS]; VISIBLE_LINES as usize];
+        let mut replay = log.replay(bus, switches, render_cycle);
+        let end = render_cycle + 1;
+        let mut undrawn = Vec::new();
+
+        for offset in 0..CYCLES_PER_FRAME {
+            let position = BeamPosition::at(end + offset);
+            if !position.visible() {
+                continue;
+            }
+            match (end + offset).checked_sub(CYCLES_PER_FRAME) {
+                Some(cycle) => {
+                    replay.advance_to(cycle);
+                    let switches = replay.switches();
+                    fetches[position.line as usize][position.column as usize] = self.fetch(
+                        Some(&switches),
+                        flash_inverted(cycle),
+                        position,
+                        |addr, aux| replay.peek(addr, aux),
+                    );
+                }
+                None => undrawn.push(position),
+            }
+        }
+
+        replay.advance_to(end);
+        let switches = replay.switches();
+        let flash = flash_inverted(render_cycle);
+        for position in undrawn {
+            fetches[position.line as usize][position.column as usize] =
+                self.fetch(Some(&switches), flash, position, |addr, aux| {
+                    replay.peek(addr, aux)
+                });
+        }
+        fetches
+    }
+
+    // The main and aux bytes under the beam. Text and lo-res fetch from the
+    // text page, hi-res from its own page.
+    fn fetch(
+        &self,
+        switches: Option<&VideoSwitches>,
+        flash: bool,
+        position: BeamPosition,
+        peek: impl Fn(u16, bool) -> u8,
+    ) -> ColumnFetch {
+        let page2 = switches.is_some_and(|switches| switches.display_page2());
+        let row_addr = match switches.map(|switches| switches.mode_at(position.line)) {
+            Some(DisplayMode::HiRes | DisplayMode::DoubleHiRes) => {
+                hires::row_address(if page2 { 0x4000 } else { 0x2000 }, position.line)
+            }
+            Some(_) => {
+                let base = if page2 { 0x0800 } else { 0x0400 };
+                self.addressing
+                    .row_address(base, position.line as usize / GLYPH_HEIGHT)
+            }
+            None => self
+                .addressing
+                .row_address(self.text_base, position.line as usize / GLYPH_HEIGHT),
+        };
+        let addr = row_addr.wrapping_add(position.column as u16);
+        ColumnFetch {
+            switches: switches.copied(),
+            flash,
+            main: peek(addr, false),
+            aux: peek(addr, true),
+        }
+    }
+
+    // Turns one scanline's fetches into pixels. Every mode also shifts its dots
+    // into a 560-dot line: hi-res colors depend on neighboring dots, and the
+    // composite backend decodes the whole line from them.
+    fn render_line(
+        &self,
+        line: u16,
+        frame: u64,
+        fetches: &[ColumnFetch; TEXT_COLS],
+        out: &mut ScreenBuffer,
+    ) {
+        let mut pixels = [COLOR_BLACK; FRAME_WIDTH];
+        let mut dots = [false; FRAME_WIDTH];
+        let mut dot_modes = [None; TEXT_COLS];
+        let mut previous_dot = false;
+
+        for (column, fetch) in fetches.iter().enumerate() {
+            let x0 = column * CELL_WIDTH;
+            let cell = &mut pixels[x0..x0 + CELL_WIDTH];
+            let switches = fetch.switches.as_ref();
+            let mode = switches.map(|switches| switches.mode_at(line));
+            match mode {
+                Some(DisplayMode::HiRes) => {
+                    dot_modes[column] = mode;
+                    previous_dot = hires::shift_byte(fetch.main, x0, previous_dot, &mut dots);
+                    continue;
+                }
+                Some(DisplayMode::DoubleHiRes) => {
+                    dot_modes[column] = mode;
+                    previous_dot = hires::shift_double(fetch.aux, fetch.main, x0, &mut dots);
+                    continue;
+                }
+                Some(DisplayMode::LoRes) => {
+                    let nibble = lores_nibble(fetch.main, line);
+                    cell.fill(LORES_PALETTE[nibble as usize]);
+                    pattern_dots(nibble, x0, &mut dots[x0..x0 + CELL_WIDTH]);
+                }
+                Some(DisplayMode::DoubleLoRes) => {
+                    // Aux blocks sit one dot earlier in the color cycle, so their
+                    // nibbles show rotated one bit left.
+                    let aux = lores_nibble(fetch.aux, line);
+                    let rotated = ((aux << 1) | (aux >> 3)) & 0x0f;
+                    cell[..GLYPH_WIDTH].fill(LORES_PALETTE[rotated as usize]);
+                    pattern_dots(aux, x0 + 3, &mut dots[x0..x0 + GLYPH_WIDTH]);
+                    let main = lores_nibble(fetch.main, line);
+                    cell[GLYPH_WIDTH..].fill(LORES_PALETTE[main as usize]);
+                    let x1 = x0 + GLYPH_WIDTH;
+                    pattern_dots(main, x1, &mut dots[x1..x1 + GLYPH_WIDTH]);
+                }
+                _ if switches.is_some_and(|switches| switches.col80) => {
+                    // 80 columns: aux then main, each glyph at the native dot width.
+                    for (half, byte) in [fetch.aux, fetch.main].into_iter().enumerate() {
+                        let bits = glyph_row(byte, switches, fetch.flash, line);
+                        glyph_dots(bits, 1, &mut dots[x0 + half * GLYPH_WIDTH..]);
+                    }
+                    self.mono_cell(&dots[x0..x0 + CELL_WIDTH], cell);
+                }
+                _ => {
+                    let bits = glyph_row(fetch.main, switches, fetch.flash, line);
+                    glyph_dots(bits, 2, &mut dots[x0..]);
+                    self.mono_cell(&dots[x0..x0 + CELL_WIDTH], cell);
+                }
+            }
+            previous_dot = false;
+        }
+
+        match self.backend {
+            VideoBackend::Digital => {
+                if dot_modes.iter().any(Option::is_some) {
+                    self.color_dots(fetches, &dots, &dot_modes, &mut pixels);
+                }
+            }
+            VideoBackend::Composite(decoder) => {
+                // The IIe sends burst whenever TEXT is off, mixed-mode text included.
+                let burst = fetches[0].switches.is_some_and(|switches| !switches.text);
+                let mut samples = composite::encode_line(&dots, burst);
+                decoder.impairments.apply(&mut samples, frame, line);
+                decoder.decode_line(&samples, &mut pixels);
+            }
+        }
+
+        for y in self.geometry.output_rows(line) {
+            for (x, color) in pixels.iter().enumerate() {
+                let _ = out.set_pixel(x, y, *color);
+            }
+        }
+    }
+
+    // Colors the hi-res and double hi-res columns of a line. Artifact reads
+    // both from the dot stream like a composite monitor; idealized double
+    // hi-res takes one color per aligned 4-dot group.
+    fn color_dots(
+        &self,
+        fetches: &[ColumnFetch; TEXT_COLS],
+        dots: &[bool; FRAME_WIDTH],
+        dot_modes: &[Option<DisplayMode>; TEXT_COLS],
+        pixels: &mut [u32; FRAME_WIDTH],
+    ) {
+        let mut ideal = [false; hires::HIRES_COLS];
+        let mut palette = [false; TEXT_COLS];
+        if self.hires_color == HiresColor::Idealized {
+            for (column, fetch) in fetches.iter().enumerate() {
+                palette[column] = fetch.main & 0x80 != 0;
+                for bit in 0..GLYPH_WIDTH {
+                    ideal[column * GLYPH_WIDTH + bit] = (fetch.main >> bit) & 1 != 0;
+                }
             }
         }
+
+        for (x, pixel) in pixels.iter_mut().enumerate() {
+            let Some(mode) = dot_modes[x / CELL_WIDTH] else {
+                continue;
+            };
+            *pixel = match (self.hires_color, mode) {
+                (HiresColor::Artifact, _) => hires::artifact_color(dots, x),
+                (HiresColor::Idealized, DisplayMode::DoubleHiRes) => hires::aligned_color(dots, x),
+                (HiresColor::Idealized, _) => hires::idealized_color(&ideal, &palette, x / 2),
+                (HiresColor::Monochrome, _) => self.mono_color(dots[x]),
+            };
+        }
+    }
+
+    fn mono_color(&self, on: bool) -> u32 {
+        if on {
+            self.foreground_color
+        } else {
+            COLOR_BLACK
+        }
+    }
+
+    fn mono_cell(&self, dots: &[bool], cell: &mut [u32]) {
+        for (pixel, on) in cell.iter_mut().zip(dots) {
+            *pixel = self.mono_color(*on);
+        }
+    }
+}
+
+// Seven glyph dots, `dot_width` dots each.
+fn glyph_dots(bits: u8, dot_width: usize, dots: &mut [bool]) {
+    for (x, dot) in dots.iter_mut().take(GLYPH_WIDTH * dot_width).enumerate() {
+        // Apple IIe glyph rows in this ROM table are stored LSB-left for 7-bit pixels.
+        *dot = ((bits >> (x / dot_width)) & 0x01) != 0;
+    }
+}
+
+// A lo-res nibble as dots: each dot shows the nibble bit for its place in the
+// 4-dot color cycle, starting at `phase`.
+fn pattern_dots(nibble: u8, phase: usize, dots: &mut [bool]) {
+    for (x, dot) in dots.iter_mut().enumerate() {
+        *dot = (nibble >> ((phase + x) % 4)) & 1 != 0;
+    }
+}
+
+// What the video hardware fetched for one column of one scanline.
+#[derive(Debug, Clone, Copy, Default)]
+struct ColumnFetch {
+    switches: Option<VideoSwitches>,
+    flash: bool,
+    main: u8,
+    aux: u8,
+}
+
+// Lo-res bytes hold two blocks: the low nibble colors the top half of the
+// text row, the high nibble the bottom half.
+fn lores_nibble(byte: u8, line: u16) -> u8 {
+    if line as usize % GLYPH_HEIGHT < GLYPH_HEIGHT / 2 {
+        byte & 0x0f
+    } else {
+        byte >> 4
+    }
+}
+
+// The 7 pixels of one glyph scanline. With IIe switches, the primary set shows
+// $00-$3F inverse and $40-$7F flashing; ALTCHARSET swaps in the alternate set
+// with MouseText at $40-$5F and inverse lowercase at $60-$7F. Flashing glyphs
+// are stored inverse and complemented on the FLASH phase.
+pub fn glyph_row(ch: u8, switches: Option<&VideoSwitches>, flash: bool, line: u16) -> u8 {
+    let bank = match switches {
+        None => NORMAL_BANK_OFFSET,
+        Some(switches) if switches.altcharset => ALTERNATE_BANK_OFFSET,
+        Some(_) => PRIMARY_BANK_OFFSET,
+    };
+    let glyph_base = bank + ch as usize * GLYPH_HEIGHT;
+
+    debug_assert!(glyph_base + GLYPH_HEIGHT <= TEXT_DISPLAY_ROM.len());
+
+    let bits = TEXT_DISPLAY_ROM[glyph_base + line as usize % GLYPH_HEIGHT] & 0x7f;
+    let flashing = bank == PRIMARY_BANK_OFFSET && (0x40..0x80).contains(&ch);
+    if flashing && flash {
+        !bits & 0x7f
+    } else {
+        bits
     }
 }
@@ -0,0 +1,120 @@
+use super::VideoSwitches;
+use crate::scheduler::{CYCLES_PER_SCANLINE, SCANLINES_PER_FRAME};
+
+pub const VISIBLE_COLUMNS: u8 = 40;
+pub const VISIBLE_LINES: u16 = 192;
+pub const CYCLES_PER_FRAME: u64 = CYCLES_PER_SCANLINE * SCANLINES_PER_FRAME;
+
+// Where the video scanner is during a CPU cycle. Each scanline starts with
+// its 40 visible columns followed by 25 cycles of horizontal blanking (the
+// last of which is the stretched 65th cycle); each frame starts with its 192
+// visible lines followed by 70 lines of vertical blanking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BeamPosition {
+    pub line: u16,
+    pub column: u8,
+}
+
+impl BeamPosition {
+    pub fn at(cycle: u64) -> Self {
+        let frame_cycle = cycle % CYCLES_PER_FRAME;
+        Self {
+            line: (frame_cycle / CYCLES_PER_SCANLINE) as u16,
+            column: (frame_cycle % CYCLES_PER_SCANLINE) as u8,
+        }
+    }
+
+    pub fn hbl(&self) -> bool {
+        self.column >= VISIBLE_COLUMNS
+    }
+
+    pub fn vbl(&self) -> bool {
+        self.line >= VISIBLE_LINES
+    }
+
+    pub fn visible(&self) -> bool {
+        !self.hbl() && !self.vbl()
+    }
+
+    // The 7-bit horizontal counter: $58-$7F while visible, then $00 and
+    // $40-$57 through blanking.
+    pub fn h_counter(&self) -> u8 {
+        match self.column {
+            c if c < VISIBLE_COLUMNS => 0x58 + c,
+            VISIBLE_COLUMNS => 0x00,
+            c => 0x40 + (c - VISIBLE_COLUMNS - 1),
+        }
+    }
+
+    // The 9-bit vertical counter: $100-$1FF, then preset to $0FA for the last
+    // six lines of the frame.
+    pub fn v_counter(&self) -> u16 {
+        if self.line < 0x100 {
+            0x100 + self.line
+        } else {
+            0x0fa + (self.line - 0x100)
+        }
+    }
+
+    // The address the scanner fetches on the IIe (Understanding the Apple IIe,
+    // ch. 5). During blanking it keeps counting, so the result points at
+    // memory just outside the visible rows.
+    pub fn scanner_address(&self, switches: &VideoSwitches) -> u16 {
+        let h = self.h_counter() as u16;
+        let v = self.v_counter();
+        let bit = |value: u16, n: u16| (value >> n) & 1;
+        let (v3, v4) = (bit(v, 6), bit(v, 7));
+
+        // A 4-bit adder spreads the 24 text rows across each 128-byte block.
+        let sum = (0x0d + ((h >> 3) & 0x07) + (v4 << 3 | v3 << 2 | v4 << 1 | v3)) & 0x0f;
+        let mut addr = (h & 0x07) | (sum << 3) | (((v >> 3) & 0x07) << 7);
+
+        let page = if switches.display_page2() { 2 } else { 1 };
+        if self.hires_time(switches) {
+            addr |= (v & 0x07) << 10;
+            addr |= page << 13;
+        } else {
+            addr |= page << 10;
+        }
+        addr
+    }
+
+    // The II+ scanner adds $1000 to text and lo-res addresses during
+    // horizontal blanking.
+    pub fn ii_plus_scanner_address(&self, switches: &VideoSwitches) -> u16 {
+        let addr = self.scanner_address(switches);
+        if self.hbl() && !self.hires_time(switches) {
+            addr | 0x1000
+        } else {
+            addr
+        }
+    }
+
+    // Mixed mode switches hi-res back to text addressing for the bottom four
+    // text rows (and the matching part of vertical blanking).
+    fn hires_time(&self, switches: &VideoSwitches) -> bool {
+        let v = self.v_counter();
+        let bottom_rows = (v >> 7) & 1 == 1 && (v >> 5) & 1 == 1;
+        switches.hires && !switches.text && !(switches.mixed && bottom_rows)
+    }
+}
+
+// VBL begins at the first cycle of line 192 and ends when line 0 starts; these
+// return the next cycle (at or after `cycle`) where each edge happens.
+pub fn next_vbl_start(cycle: u64) -> u64 {
+    next_frame_offset(cycle, VISIBLE_LINES as u64 * CYCLES_PER_SCANLINE)
+}
+
+pub fn next_vbl_end(cycle: u64) -> u64 {
+    next_frame_offset(cycle, 0)
+}
+
+fn next_frame_offset(cycle: u64, offset: u64) -> u64 {
+    let frame_start = cycle - cycle % CYCLES_PER_FRAME;
+    let edge = frame_start + offset;
+    if edge >= cycle {
+        edge
+    } else {
+        edge + CYCLES_PER_FRAME
+    }
+}
@@ -0,0 +1,131 @@
+use echo_lab::cpu::asm::{Assembler, assemble};
+use echo_lab::cpu::disasm::{decode, disassemble_range};
+use echo_lab::cpu::opcodes::{CMOS_OPCODES, Op};
+
+#[test]
+fn disassembler_reports_mnemonic_operand_length_and_cycles() {
+    let cases: [(&[u8], &str, u16, u8); 8] = [
+        (&[0xa9, 0x42], "LDA #$42", 2, 2),
+        (&[0xbd, 0x00, 0x20], "LDA $2000,X", 3, 4),
+        (&[0xb1, 0x3c], "LDA ($3C),Y", 2, 5),
+        (&[0xb2, 0x3c], "LDA ($3C)", 2, 5),
+        (&[0x7c, 0x34, 0x12], "JMP ($1234,X)", 3, 6),
+        (&[0x1a], "INC A", 1, 2),
+        (&[0xd0, 0xfe], "BNE $0300", 2, 2),
+        (&[0x60], "RTS", 1, 6),
+    ];
+    for (bytes, text, size, cycles) in cases {
+        let instruction = decode(0x0300, bytes);
+        assert_eq!(instruction.to_string(), text);
+        assert_eq!(instruction.size(), size, "{}", text);
+        assert_eq!(instruction.cycles(), cycles, "{}", text);
+        assert_eq!(instruction.bytes(), bytes, "{}", text);
+    }
+    assert_eq!(decode(0x0300, &[0xd0, 0xfe]).operand_value(), Some(0x0300));
+}
+
+#[test]
+fn assembler_resolves_labels_constants_and_directives() {
+    let program = assemble(
+        0x0300,
+        "
+        screen = $0400
+        start:  ldx #0
+        loop    lda message,x   ; forward reference assembles as absolute
+                beq done
+                ora #$80
+                sta screen,x
+                inx
+                bra loop
+        done:   rts
+        message .byte \"HI\", 0
+                .word start, >message
+        ",
+    )
+    .expect("program should assemble");
+
+    assert_eq!(
+        program.bytes,
+        vec![
+            0xa2, 0x00, // LDX #0
+            0xbd, 0x10, 0x03, // LDA message,X
+            0xf0, 0x08, // BEQ done
+            0x09, 0x80, // ORA #$80
+            0x9d, 0x00, 0x04, // STA screen,X
+            0xe8, // INX
+            0x80, 0xf3, // BRA loop
+            0x60, // RTS
+            0x48, 0x49, 0x00, // "HI", 0
+            0x00, 0x03, 0x03, 0x00,
+        ]
+    );
+    assert_eq!(program.labels["loop"], 0x0302);
+    assert_eq!(program.labels["message"], 0x0310);
+    assert_eq!(program.end(), 0x0317);
+}
+
+#[test]
+fn assembler_picks_zero_page_modes_for_known_small_operands() {
+    let asm = Assembler::new().with_symbol("ptr", 0x3c);
+    assert_eq!(
+        asm.assemble_line(0x0300, "lda ptr").unwrap(),
+        vec![0xa5, 0x3c]
+    );
+    assert_eq!(
+        asm.assemble_line(0x0300, "lda (ptr),y").unwrap(),
+        vec![0xb1, 0x3c]
+    );
+    assert_eq!(
+        asm.assemble_line(0x0300, "stx ptr,y").unwrap(),
+        vec![0x96, 0x3c]
+    );
+    assert_eq!(
+        asm.assemble_line(0x0300, "ldx $1234,y").unwrap(),
+        vec![0xbe, 0x34, 0x12]
+    );
+    assert_eq!(
+        asm.assemble_line(0x0300, "jmp ($03f0)").unwrap(),
+        vec![0x6c, 0xf0, 0x03]
+    );
+    assert_eq!(asm.assemble_line(0x0300, "asl").unwrap(), vec![0x0a]);
+    assert_eq!(asm.assemble_line(0x0300, "nop").unwrap(), vec![0xea]);
+}
+
+#[test]
+fn assembler_reports_errors_with_line_numbers() {
+    let err = assemble(0x0300, "nop\nlda missing").expect_err("undefined label");
+    assert_eq!(err, "line 2: undefined label 'missing'");
+
+    let err = assemble(0x0300, "bne far\n.org $0400\nfar: rts").expect_err("branch range");
+    assert!(err.starts_with("line 1: branch target $0400 out of range"));
+
+    let err = assemble(0x0300, "stz ($10),y").expect_err("bad mode");
+    assert!(err.contains("STZ does not support"));
+
+    let err = assemble(0x0300, "frob #1").expect_err("bad mnemonic");
+    assert!(err.contains("unknown mnemonic 'frob'"));
+}
+
+#[test]
+fn every_documented_opcode_round_trips_through_the_assembler() {
+    let asm = Assembler::new();
+    for opcode in 0..=255u8 {
+        let info = &CMOS_OPCODES[opcode as usize];
+        if info.op == Op::Nop && opcode != 0xea {
+            continue;
+        }
+        // Pick operands that keep every mode unambiguous.
+        let bytes = [opcode, 0x12, 0x34];
+        let instruction = decode(0x0300, &bytes[..info.size() as usize]);
+        let reassembled = asm
+            .assemble_line(0x0300, &instruction.to_string())
+            .unwrap_or_else(|e| panic!("${:02X} {}: {}", opcode, instruction, e));
+        assert_eq!(reassembled, instruction.bytes(), "{}", instruction);
+    }
+
+    let listing: Vec<String> = disassemble_range(&[0xeau8; 65536], 0xfffe, 3)
+        .iter()
+        .map(|i| format!("{:04X}", i.addr))
+        .collect();
+    assert_eq!(listing, ["FFFE", "FFFF", "0000"]);
+}
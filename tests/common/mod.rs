@@ -0,0 +1,47 @@
+// Fixtures shared by the video tests; each test crate uses a subset.
+#![allow(dead_code)]
+
+use echo_lab::bus::Bus;
+use echo_lab::machines::iie::memory::IieMemory;
+use echo_lab::screen_buffer::ScreenBuffer;
+use echo_lab::video::scanner::CYCLES_PER_FRAME;
+use echo_lab::video::{FRAME_WIDTH, TextVideoController};
+
+// Two frames in, the beam has redrawn everything since switches set at cycle 0.
+pub const SETTLED: u64 = 2 * CYCLES_PER_FRAME;
+
+// A IIe with the given soft switches hit at cycle 0. $C000-$C00F switches
+// take writes; the rest toggle on reads.
+pub fn iie_with(switches: &[u16]) -> IieMemory {
+    let mut mem = IieMemory::new();
+    for &addr in switches {
+        set_switch(&mut mem, addr, 0);
+    }
+    mem
+}
+
+pub fn set_switch(mem: &mut IieMemory, addr: u16, cycle: u64) {
+    if addr < 0xc010 {
+        mem.write(addr, 0, cycle);
+    } else {
+        mem.read(addr, cycle);
+    }
+}
+
+pub fn render(mem: &IieMemory, cycle: u64, video: TextVideoController) -> ScreenBuffer {
+    let (width, height) = video.frame_dimensions();
+    let mut out = ScreenBuffer::new(width, height);
+    video.render_frame(mem, cycle, &mut out);
+    out
+}
+
+// Output row for a scanline in the default Scanlines geometry.
+pub fn y(line: usize) -> usize {
+    line * 2
+}
+
+pub fn row(frame: &ScreenBuffer, line: usize) -> Vec<u32> {
+    (0..FRAME_WIDTH)
+        .map(|x| frame.get_pixel(x, y(line)).unwrap_or(0))
+        .collect()
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use common::{SETTLED, iie_with, row, set_switch};
+use echo_lab::machines::iie::memory::IieMemory;
+use echo_lab::screen_buffer::ScreenBuffer;
+use echo_lab::video::composite::{
+    BURST_AMPLITUDE, CompositeDecoder, SYNC_LEVEL, VideoBackend, YiqFilter, encode_line,
+    measure_burst,
+};
+use echo_lab::video::scanner::CYCLES_PER_FRAME;
+use echo_lab::video::{COLOR_BLACK, COLOR_WHITE, FRAME_WIDTH, LORES_PALETTE, TextVideoController};
+
+fn render(mem: &IieMemory, cycle: u64, decoder: CompositeDecoder) -> ScreenBuffer {
+    common::render(
+        mem,
+        cycle,
+        TextVideoController::default().with_backend(VideoBackend::Composite(decoder)),
+    )
+}
+
+fn channels(color: u32) -> [u32; 3] {
+    [(color >> 16) & 0xff, (color >> 8) & 0xff, color & 0xff]
+}
+
+fn neutral(color: u32) -> bool {
+    let [r, g, b] = channels(color);
+    r.abs_diff(g) <= 2 && g.abs_diff(b) <= 2
+}
+
+fn brightest(color: u32) -> usize {
+    let rgb = channels(color);
+    (0..3).max_by_key(|&c| rgb[c]).unwrap_or(0)
+}
+
+#[test]
+fn burst_and_sync_frame_the_active_line() {
+    let dots = [true; FRAME_WIDTH];
+    let color = encode_line(&dots, true);
+    assert_eq!(color.len(), 912);
+    assert_eq!(color[0], SYNC_LEVEL);
+    let burst = measure_burst(&color);
+    assert!((burst.amplitude - BURST_AMPLITUDE).abs() < 0.01);
+
+    let mono = encode_line(&dots, false);
+    assert_eq!(measure_burst(&mono).amplitude, 0.0);
+
+    assert_eq!(VideoBackend::parse("Digital"), Ok(VideoBackend::Digital));
+    assert_eq!(
+        VideoBackend::parse("composite"),
+        Ok(VideoBackend::Composite(CompositeDecoder::default()))
+    );
+    assert!(VideoBackend::parse("rgb").is_err());
+}
+
+#[test]
+fn solid_lores_colors_decode_to_their_hues() {
+    let mut decoded = Vec::new();
+    for nibble in 0..16u8 {
+        let mut mem = iie_with(&[0xc050]); // TEXT off
+        mem.main_mut()[0x0400..0x0800].fill(nibble | nibble << 4);
+        let line = row(&render(&mem, SETTLED, CompositeDecoder::default()), 0);
+        // Away from the edges, where the filters see the blanking level.
+        assert!(line[20..540].iter().all(|pixel| *pixel == line[280]));
+        decoded.push(line[280]);
+    }
+
+    assert_eq!(decoded[0x0], COLOR_BLACK);
+    assert_eq!(decoded[0xf], COLOR_WHITE);
+    assert!(neutral(decoded[0x5]) && neutral(decoded[0xa]));
+    for (nibble, color) in decoded.iter().enumerate() {
+        assert!(
+            !decoded[..nibble].contains(color),
+            "color {} repeats",
+            nibble
+        );
+    }
+    for nibble in [0x1, 0x2, 0x4, 0x6, 0x9, 0xc] {
+        assert_eq!(
+            brightest(decoded[nibble]),
+            brightest(LORES_PALETTE[nibble]),
+            "color {:x} decoded as {:06x}",
+            nibble,
+            decoded[nibble] & 0xff_ffff
+        );
+    }
+}
+
+#[test]
+fn text_kills_color_but_mixed_mode_text_fringes() {
+    let mut mem = IieMemory::new();
+    mem.main_mut()[0x0400..0x0800].fill(b'H' | 0x80);
+    let text = render(&mem, SETTLED, CompositeDecoder::default());
+    assert!(
+        row(&text, 163)
+            .iter()
+            .all(|pixel| *pixel == COLOR_BLACK || *pixel == COLOR_WHITE)
+    );
+
+    // Mixed mode keeps burst on for the text rows.
+    set_switch(&mut mem, 0xc050, 0); // TEXT off
+    set_switch(&mut mem, 0xc053, 0); // MIXED on
+    let mixed = render(&mem, 4 * CYCLES_PER_FRAME, CompositeDecoder::default());
+    assert!(row(&mixed, 163).iter().any(|pixel| !neutral(*pixel)));
+}
+
+#[test]
+fn wider_chroma_filters_smear_color_further() {
+    let colored = |filter: YiqFilter| {
+        let mut mem = iie_with(&[0xc050, 0xc057]); // TEXT off, HIRES on
+        mem.main_mut()[0x2000 + 20] = 0x01; // one lone dot
+        let decoder = CompositeDecoder::default().with_filter(filter);
+        row(&render(&mem, SETTLED, decoder), 0)
+            .iter()
+            .filter(|pixel| !neutral(**pixel))
+            .count()
+    };
+    let sharp = colored(YiqFilter::default());
+    let soft = colored(YiqFilter {
+        i: 24,
+        q: 32,
+        ..YiqFilter::default()
+    });
+    assert!(sharp > 0);
+    assert!(soft > sharp, "{} vs {}", soft, sharp);
+}
@@ -0,0 +1,187 @@
+mod common;
+
+use common::{SETTLED, iie_with, row};
+use echo_lab::machines::iie::memory::IieMemory;
+use echo_lab::screen_buffer::ScreenBuffer;
+use echo_lab::video::composite::{CompositeDecoder, Impairments, VideoBackend};
+use echo_lab::video::scanner::CYCLES_PER_FRAME;
+use echo_lab::video::{COLOR_BLACK, FRAME_WIDTH, TextVideoController};
+
+// A hi-res screen with one solid bar in columns 10 and 11 on every line.
+fn bar_memory() -> IieMemory {
+    let mut mem = iie_with(&[0xc050, 0xc057]); // TEXT off, HIRES on
+    for line in 0..192 {
+        let addr = echo_lab::video::hires::row_address(0x2000, line) as usize;
+        mem.main_mut()[addr + 10] = 0x7f;
+        mem.main_mut()[addr + 11] = 0x7f;
+    }
+    mem
+}
+
+fn render(mem: &IieMemory, impairments: Impairments) -> ScreenBuffer {
+    render_at(mem, SETTLED, impairments)
+}
+
+fn render_at(mem: &IieMemory, cycle: u64, impairments: Impairments) -> ScreenBuffer {
+    let decoder = CompositeDecoder::default().with_impairments(impairments);
+    common::render(
+        mem,
+        cycle,
+        TextVideoController::default().with_backend(VideoBackend::Composite(decoder)),
+    )
+}
+
+fn luma(color: u32) -> u32 {
+    ((color >> 16) & 0xff) + ((color >> 8) & 0xff) + (color & 0xff)
+}
+
+// The first lit pixel of the bar.
+fn bar_start_in(line: &[u32]) -> usize {
+    line.iter()
+        .position(|pixel| luma(*pixel) > 3 * 128)
+        .unwrap_or(FRAME_WIDTH)
+}
+
+fn bar_start(frame: &ScreenBuffer, line: usize) -> usize {
+    bar_start_in(&row(frame, line))
+}
+
+#[test]
+fn impairments_are_deterministic_for_a_seed() {
+    let mem = bar_memory();
+    let noisy = |seed| Impairments {
+        seed,
+        noise: 8.0,
+        jitter: 3,
+        ..Impairments::default()
+    };
+    let a = render(&mem, noisy(7));
+    let b = render(&mem, noisy(7));
+    let c = render(&mem, noisy(8));
+    let rows = |frame: &ScreenBuffer| (0..192).map(|line| row(frame, line)).collect::<Vec<_>>();
+    assert_eq!(rows(&a), rows(&b));
+    assert_ne!(rows(&a), rows(&c));
+
+    // The next frame gets fresh noise; the same frame rendered later does not.
+    let next = render_at(&mem, 3 * CYCLES_PER_FRAME, noisy(7));
+    let late = render_at(&mem, 3 * CYCLES_PER_FRAME - 1, noisy(7));
+    assert_ne!(rows(&a), rows(&next));
+    assert_eq!(rows(&a), rows(&late));
+
+    // A seed alone changes nothing.
+    let clean = render(&mem, Impairments::default());
+    let seeded = render(
+        &mem,
+        Impairments {
+            seed: 7,
+            ..Impairments::default()
+        },
+    );
+    assert_eq!(rows(&clean), rows(&seeded));
+}
+
+#[test]
+fn noise_speckles_black_and_jitter_moves_lines() {
+    let mem = bar_memory();
+    let clean = render(&mem, Impairments::default());
+    assert!(row(&clean, 0)[300..500].iter().all(|p| *p == COLOR_BLACK));
+    assert!((0..192).all(|line| bar_start(&clean, line) == bar_start(&clean, 0)));
+
+    let noisy = render(
+        &mem,
+        Impairments {
+            noise: 10.0,
+            ..Impairments::default()
+        },
+    );
+    assert!(row(&noisy, 0)[300..500].iter().any(|p| *p != COLOR_BLACK));
+
+    let jittered = render(
+        &mem,
+        Impairments {
+            jitter: 4,
+            ..Impairments::default()
+        },
+    );
+    let home = bar_start(&clean, 0);
+    let starts: Vec<usize> = (0..192).map(|line| bar_start(&jittered, line)).collect();
+    assert!(starts.iter().all(|x| x.abs_diff(home) <= 4));
+    assert!(starts.iter().any(|x| *x != home));
+}
+
+#[test]
+fn jitter_wider_than_a_line_is_held_inside_it() {
+    let mem = bar_memory();
+    let frame = render(
+        &mem,
+        Impairments {
+            jitter: 1000,
+            ..Impairments::default()
+        },
+    );
+    let clean = render(&mem, Impairments::default());
+    assert!((0..192).any(|line| row(&frame, line) != row(&clean, line)));
+}
+
+#[test]
+fn ghost_repeats_the_bar_late_and_faint() {
+    let mem = bar_memory();
+    let clean = row(&render(&mem, Impairments::default()), 0);
+    let ghosted = row(
+        &render(
+            &mem,
+            Impairments {
+                ghost_delay: 100,
+                ghost_gain: 0.3,
+                ..Impairments::default()
+            },
+        ),
+        0,
+    );
+    let start = bar_start_in(&clean);
+    assert_eq!(clean[start + 100 + 10], COLOR_BLACK);
+    let echo = luma(ghosted[start + 100 + 10]);
+    assert!(
+        echo > 0 && echo < luma(ghosted[start + 10]) / 2,
+        "echo {}",
+        echo
+    );
+}
+
+#[test]
+fn bandwidth_softens_edges_and_phase_error_shifts_hue() {
+    let mem = bar_memory();
+    let clean = row(&render(&mem, Impairments::default()), 0);
+    let soft = row(
+        &render(
+            &mem,
+            Impairments {
+                luma_bandwidth: 12,
+                ..Impairments::default()
+            },
+        ),
+        0,
+    );
+    let start = bar_start_in(&clean);
+    let middle = start + 10;
+    assert_eq!(clean[middle], soft[middle]);
+    // Just left of the edge the soft line has picked up light from the bar.
+    assert!(luma(soft[start - 4]) > luma(clean[start - 4]));
+
+    // Lo-res magenta turns toward another hue under a 90 degree error.
+    let mut mem = iie_with(&[0xc050]);
+    mem.main_mut()[0x0400..0x0800].fill(0x11);
+    let clean = row(&render(&mem, Impairments::default()), 0)[280];
+    let shifted = row(
+        &render(
+            &mem,
+            Impairments {
+                chroma_phase: 90.0,
+                ..Impairments::default()
+            },
+        ),
+        0,
+    )[280];
+    assert_ne!(clean, shifted);
+    assert!(luma(clean).abs_diff(luma(shifted)) < luma(clean) / 2);
+}
@@ -1,4 +1,6 @@
 use echo_lab::config::EchoLabConfig;
+use echo_lab::machines::ram_init::RamInit;
+use echo_lab::video::composite::VideoBackend;
 
 #[test]
 fn parse_config_overrides_defaults() {
@@ -21,3 +23,80 @@ fn parse_config_uses_defaults_when_values_missing() {
     assert_eq!(cfg.sdl3_text40x24.default_screenshot_dir, "screenshots");
     assert_eq!(cfg.sdl3_text40x24.auto_exit_seconds, 5);
 }
+
+#[test]
+fn parse_config_reads_system_rom_section() {
+    let cfg = EchoLabConfig::from_toml_like(
+        r#"
+[system_rom]
+path = "roms/apple2e.rom"
+sha1 = "0123456789abcdef0123456789abcdef01234567"
+"#,
+    )
+    .expect("config should parse");
+
+    assert_eq!(cfg.system_rom.path.as_deref(), Some("roms/apple2e.rom"));
+    assert_eq!(
+        cfg.system_rom.sha1.as_deref(),
+        Some("0123456789abcdef0123456789abcdef01234567")
+    );
+    assert_eq!(EchoLabConfig::default().system_rom.path, None);
+}
+
+#[test]
+fn parse_config_reads_machine_ram_init() {
+    let cfg = EchoLabConfig::from_toml_like("[machine]\nram_init = \"random:0x1234\"\n")
+        .expect("config should parse");
+    assert_eq!(cfg.machine.ram_init, RamInit::Random(0x1234));
+
+    let err = EchoLabConfig::from_toml_like("[machine]\nram_init = \"sparkly\"\n")
+        .expect_err("unknown pattern should fail");
+    assert!(err.contains("line 2"));
+}
+
+#[test]
+fn parse_config_reads_composite_impairments() {
+    let cfg = EchoLabConfig::from_toml_like(
+        r#"
+[composite]
+seed = 42
+noise = 6.5
+ghost_delay = 40
+ghost_gain = 0.25
+jitter = 2
+"#,
+    )
+    .expect("config should parse");
+
+    let impairments = cfg.composite.expect("section sets impairments");
+    assert_eq!(impairments.seed, 42);
+    assert_eq!(impairments.noise, 6.5);
+    assert_eq!(impairments.ghost_delay, 40);
+    assert_eq!(impairments.ghost_gain, 0.25);
+    assert_eq!(impairments.jitter, 2);
+    assert_eq!(impairments.luma_bandwidth, 0);
+    assert_eq!(EchoLabConfig::default().composite, None);
+
+    // Only the composite backend carries them.
+    assert_eq!(
+        VideoBackend::Digital.with_impairments(impairments),
+        VideoBackend::Digital
+    );
+    let VideoBackend::Composite(decoder) = VideoBackend::parse("composite")
+        .expect("backend should parse")
+        .with_impairments(impairments)
+    else {
+        panic!("composite backend expected");
+    };
+    assert_eq!(decoder.impairments, impairments);
+
+    let err = EchoLabConfig::from_toml_like("[composite]\njitter = -1\n")
+        .expect_err("negative jitter should fail");
+    assert!(err.contains("invalid jitter on line 2"));
+    let err = EchoLabConfig::from_toml_like("[composite]\njitter = 1000\n")
+        .expect_err("jitter past the line should fail");
+    assert!(err.contains("must be below 912 samples"), "{}", err);
+    let err = EchoLabConfig::from_toml_like("[composite]\nnosie = 4\n")
+        .expect_err("unknown key should fail");
+    assert!(err.contains("unknown [composite] key 'nosie' on line 2"));
+}
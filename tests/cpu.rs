@@ -0,0 +1,195 @@
+use echo_lab::bus::Bus;
+use echo_lab::cpu::{Cpu, FLAG_C, FLAG_N, FLAG_V, FLAG_Z};
+
+fn load(program: &[u8], origin: u16) -> (Cpu, [u8; 65536]) {
+    let mut mem = [0u8; 65536];
+    mem[origin as usize..origin as usize + program.len()].copy_from_slice(program);
+    let mut cpu = Cpu::new();
+    cpu.pc = origin;
+    (cpu, mem)
+}
+
+#[test]
+fn lda_absolute_x_pays_one_cycle_only_on_page_cross() {
+    // LDX #$01; LDA $10FE,X; LDA $10FF,X
+    let (mut cpu, mut mem) = load(&[0xa2, 0x01, 0xbd, 0xfe, 0x10, 0xbd, 0xff, 0x10], 0x0300);
+    mem[0x10ff] = 0x42;
+    mem[0x1100] = 0x80;
+
+    assert_eq!(cpu.step(&mut mem), 2);
+    assert_eq!(cpu.step(&mut mem), 4);
+    assert_eq!(cpu.a, 0x42);
+    assert_eq!(cpu.step(&mut mem), 5);
+    assert_eq!(cpu.a, 0x80);
+    assert!(cpu.flag(FLAG_N));
+    assert_eq!(cpu.cycles, 11);
+}
+
+#[test]
+fn branches_add_cycles_when_taken_and_when_crossing_pages() {
+    // $02F0: BNE +2 (not taken, Z set); BEQ +$10 (taken, crosses to $0304)
+    let (mut cpu, mut mem) = load(&[0xd0, 0x02, 0xf0, 0x10], 0x02f0);
+    cpu.set_flag(FLAG_Z, true);
+
+    assert_eq!(cpu.step(&mut mem), 2);
+    assert_eq!(cpu.step(&mut mem), 4);
+    assert_eq!(cpu.pc, 0x0304);
+
+    // BRA is always 3 cycles without a page cross.
+    let (mut cpu, mut mem) = load(&[0x80, 0x05], 0x0300);
+    assert_eq!(cpu.step(&mut mem), 3);
+    assert_eq!(cpu.pc, 0x0307);
+}
+
+#[test]
+fn decimal_mode_adc_and_sbc_produce_bcd_and_take_an_extra_cycle() {
+    // SED; CLC; LDA #$58; ADC #$46; SEC; SBC #$05
+    let (mut cpu, mut mem) = load(
+        &[0xf8, 0x18, 0xa9, 0x58, 0x69, 0x46, 0x38, 0xe9, 0x05],
+        0x0300,
+    );
+    cpu.step(&mut mem);
+    cpu.step(&mut mem);
+    cpu.step(&mut mem);
+
+    assert_eq!(cpu.step(&mut mem), 3);
+    assert_eq!(cpu.a, 0x04);
+    assert!(cpu.flag(FLAG_C));
+    assert!(!cpu.flag(FLAG_Z));
+
+    cpu.step(&mut mem);
+    assert_eq!(cpu.step(&mut mem), 3);
+    assert_eq!(cpu.a, 0x99);
+    assert!(!cpu.flag(FLAG_C));
+    assert!(cpu.flag(FLAG_N));
+}
+
+#[test]
+fn cmos_only_instructions_behave_as_documented() {
+    let program = [
+        0xa9, 0x0f, // LDA #$0F
+        0x89, 0xf0, // BIT #$F0 (Z only)
+        0x64, 0x10, // STZ $10
+        0x04, 0x11, // TSB $11
+        0x14, 0x12, // TRB $12
+        0xa2, 0x77, // LDX #$77
+        0xda, // PHX
+        0xa2, 0x00, // LDX #$00
+        0xfa, // PLX
+        0xb2, 0x20, // LDA ($20)
+    ];
+    let (mut cpu, mut mem) = load(&program, 0x0300);
+    mem[0x10] = 0xff;
+    mem[0x11] = 0x30;
+    mem[0x12] = 0xff;
+    mem[0x20] = 0x00;
+    mem[0x21] = 0x40;
+    mem[0x4000] = 0x5a;
+
+    cpu.step(&mut mem);
+    cpu.set_flag(FLAG_N, true);
+    cpu.set_flag(FLAG_V, true);
+    assert_eq!(cpu.step(&mut mem), 2);
+    assert!(cpu.flag(FLAG_Z));
+    assert!(cpu.flag(FLAG_N) && cpu.flag(FLAG_V));
+
+    assert_eq!(cpu.step(&mut mem), 3);
+    assert_eq!(mem[0x10], 0x00);
+
+    assert_eq!(cpu.step(&mut mem), 5);
+    assert_eq!(mem[0x11], 0x3f);
+    assert!(cpu.flag(FLAG_Z));
+
+    assert_eq!(cpu.step(&mut mem), 5);
+    assert_eq!(mem[0x12], 0xf0);
+    assert!(!cpu.flag(FLAG_Z));
+
+    cpu.step(&mut mem);
+    assert_eq!(cpu.step(&mut mem), 3);
+    cpu.step(&mut mem);
+    assert_eq!(cpu.step(&mut mem), 4);
+    assert_eq!(cpu.x, 0x77);
+
+    assert_eq!(cpu.step(&mut mem), 5);
+    assert_eq!(cpu.a, 0x5a);
+}
+
+#[test]
+fn jmp_indirect_does_not_wrap_within_page_and_jsr_rts_round_trip() {
+    // JMP ($10FF) reads the high byte from $1100 on the 65C02.
+    let (mut cpu, mut mem) = load(&[0x6c, 0xff, 0x10], 0x0300);
+    mem[0x10ff] = 0x00;
+    mem[0x1100] = 0x20;
+    mem[0x1000] = 0x30;
+    assert_eq!(cpu.step(&mut mem), 6);
+    assert_eq!(cpu.pc, 0x2000);
+
+    // JSR $0400; at $0400: RTS
+    let (mut cpu, mut mem) = load(&[0x20, 0x00, 0x04], 0x0300);
+    mem[0x0400] = 0x60;
+    assert_eq!(cpu.step(&mut mem), 6);
+    assert_eq!(cpu.pc, 0x0400);
+    assert_eq!(cpu.sp, 0xfb);
+    assert_eq!(cpu.step(&mut mem), 6);
+    assert_eq!(cpu.pc, 0x0303);
+    assert_eq!(cpu.sp, 0xfd);
+}
+
+#[test]
+fn read_modify_write_abs_x_timing_matches_65c02() {
+    // LDX #$01; ASL $10FF,X; INC $1000,X
+    let (mut cpu, mut mem) = load(&[0xa2, 0x01, 0x1e, 0xff, 0x10, 0xfe, 0x00, 0x10], 0x0300);
+    mem[0x1100] = 0x81;
+    cpu.step(&mut mem);
+
+    assert_eq!(cpu.step(&mut mem), 7);
+    assert_eq!(mem[0x1100], 0x02);
+    assert!(cpu.flag(FLAG_C));
+
+    assert_eq!(cpu.step(&mut mem), 7);
+    assert_eq!(mem[0x1001], 0x01);
+}
+
+#[test]
+fn bus_accesses_are_stamped_with_instruction_cycles() {
+    struct RecordingBus {
+        ram: [u8; 65536],
+        log: Vec<(u16, u64, bool)>,
+    }
+
+    impl Bus for RecordingBus {
+        fn read(&mut self, addr: u16, cycle: u64) -> u8 {
+            self.log.push((addr, cycle, false));
+            self.ram[addr as usize]
+        }
+
+        fn write(&mut self, addr: u16, value: u8, cycle: u64) {
+            self.log.push((addr, cycle, true));
+            self.ram[addr as usize] = value;
+        }
+
+        fn peek(&self, addr: u16) -> u8 {
+            self.ram[addr as usize]
+        }
+    }
+
+    // NOP; STA $2000
+    let (mut cpu, ram) = load(&[0xea, 0x8d, 0x00, 0x20], 0x0300);
+    let mut bus = RecordingBus {
+        ram,
+        log: Vec::new(),
+    };
+
+    cpu.step(&mut bus);
+    cpu.step(&mut bus);
+    assert_eq!(
+        bus.log,
+        vec![
+            (0x0300, 0, false),
+            (0x0301, 2, false),
+            (0x0302, 3, false),
+            (0x0303, 4, false),
+            (0x2000, 5, true),
+        ]
+    );
+}
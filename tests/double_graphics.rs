@@ -0,0 +1,117 @@
+mod common;
+
+use common::{SETTLED, iie_with, row};
+use echo_lab::bus::Bus;
+use echo_lab::machines::ii_plus;
+use echo_lab::machines::iie::memory::IieMemory;
+use echo_lab::screen_buffer::ScreenBuffer;
+use echo_lab::video::hires::HiresColor;
+use echo_lab::video::{
+    COLOR_BLACK, COLOR_PHOSPHOR_GREEN, DisplayMode, GLYPH_WIDTH, LORES_PALETTE, TextVideoController,
+};
+
+// TEXT off, 80COL on, AN3 off; HIRES picks double hi-res over double lo-res.
+const DOUBLE_LORES: [u16; 3] = [0xc050, 0xc00d, 0xc05e];
+const DOUBLE_HIRES: [u16; 4] = [0xc050, 0xc00d, 0xc05e, 0xc057];
+
+fn render(mem: &IieMemory, model: HiresColor) -> ScreenBuffer {
+    common::render(
+        mem,
+        SETTLED,
+        TextVideoController::default().with_hires_color(model),
+    )
+}
+
+#[test]
+fn an3_with_80col_selects_the_double_modes() {
+    let mut mem = IieMemory::new();
+    assert_eq!(mem.read(0xc07f, 0) & 0x80, 0);
+    mem.read(0xc050, 0);
+    mem.read(0xc057, 0);
+    mem.read(0xc05e, 0);
+    assert_eq!(mem.read(0xc07f, 0) & 0x80, 0x80);
+    let switches = mem.video_switches().expect("IIe switches");
+    assert_eq!(switches.mode_at(0), DisplayMode::HiRes);
+
+    mem.write(0xc00d, 0, 0);
+    let switches = mem.video_switches().expect("IIe switches");
+    assert_eq!(switches.mode_at(0), DisplayMode::DoubleHiRes);
+    mem.read(0xc056, 0);
+    let switches = mem.video_switches().expect("IIe switches");
+    assert_eq!(switches.mode_at(0), DisplayMode::DoubleLoRes);
+    mem.read(0xc05f, 0);
+    assert_eq!(mem.read(0xc07f, 0) & 0x80, 0);
+
+    // The II+ has no double modes.
+    let mut plus = ii_plus::apple_ii_plus();
+    plus.memory.read(0xc05e, 0);
+    assert!(!plus.memory.mmu().dhires);
+}
+
+#[test]
+fn double_hires_shifts_aux_then_main_at_full_dot_rate() {
+    let mut mem = iie_with(&DOUBLE_HIRES);
+    mem.aux_mut()[0x2000] = 0x81; // palette bit ignored
+    mem.main_mut()[0x2000] = 0x40;
+    mem.aux_mut()[0x2001] = 0x02;
+    let frame = render(&mem, HiresColor::Monochrome);
+    let lit: Vec<usize> = row(&frame, 0)
+        .iter()
+        .enumerate()
+        .filter(|(_, color)| **color == COLOR_PHOSPHOR_GREEN)
+        .map(|(x, _)| x)
+        .collect();
+    assert_eq!(lit, vec![0, 13, 15]);
+}
+
+#[test]
+fn double_hires_shows_sixteen_colors_in_both_color_models() {
+    for (color, expected) in LORES_PALETTE.iter().enumerate() {
+        let mut mem = iie_with(&DOUBLE_HIRES);
+        // Repeat the color's dot pattern along line 0, aux byte first.
+        for col in 0..40 {
+            for (half, aux) in [true, false].into_iter().enumerate() {
+                let mut byte = 0u8;
+                for bit in 0..GLYPH_WIDTH {
+                    let x = col * 14 + half * GLYPH_WIDTH + bit;
+                    byte |= (((color >> (x % 4)) & 1) as u8) << bit;
+                }
+                if aux {
+                    mem.aux_mut()[0x2000 + col] = byte;
+                } else {
+                    mem.main_mut()[0x2000 + col] = byte;
+                }
+            }
+        }
+        for model in [HiresColor::Idealized, HiresColor::Artifact] {
+            let line = row(&render(&mem, model), 0);
+            assert!(
+                line[4..556].iter().all(|pixel| pixel == expected),
+                "color {} in {:?}",
+                color,
+                model
+            );
+        }
+    }
+}
+
+#[test]
+fn double_lores_draws_half_width_blocks_with_rotated_aux_colors() {
+    let mut mem = iie_with(&DOUBLE_LORES);
+    mem.aux_mut()[0x0400] = 0x81;
+    mem.main_mut()[0x0400] = 0x1c;
+    let frame = render(&mem, HiresColor::Artifact);
+    let top = row(&frame, 0);
+    // Aux magenta (1) shows as dark blue (2); main nibbles match lo-res.
+    assert!(top[..7].iter().all(|pixel| *pixel == LORES_PALETTE[0x2]));
+    assert!(top[7..14].iter().all(|pixel| *pixel == LORES_PALETTE[0xc]));
+    assert_eq!(top[14], COLOR_BLACK);
+    let bottom = row(&frame, 4);
+    // Aux brown (8) rotates round to magenta (1).
+    assert!(bottom[..7].iter().all(|pixel| *pixel == LORES_PALETTE[0x1]));
+    assert!(
+        bottom[7..14]
+            .iter()
+            .all(|pixel| *pixel == LORES_PALETTE[0x1])
+    );
+}
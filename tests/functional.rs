@@ -0,0 +1,96 @@
+use echo_lab::cpu::CpuVariant;
+use echo_lab::cpu::asm::assemble;
+use echo_lab::cpu::functional::{FunctionalOutcome, FunctionalTest};
+
+// The test binaries are not distributed with the repo. Point these at local
+// builds to run them; each test is skipped when its variable is unset.
+const NMOS_ENV: &str = "ECHOLAB_6502_FUNCTIONAL_TEST";
+const EXTENDED_ENV: &str = "ECHOLAB_65C02_EXTENDED_TEST";
+
+fn run_binary(env: &str, harness: FunctionalTest) {
+    let Some(path) = std::env::var_os(env) else {
+        eprintln!("skipping: {} is not set", env);
+        return;
+    };
+    let path = path.to_str().expect("test binary path should be utf-8");
+
+    // A build with different options moves the success trap; override it in hex.
+    let harness = match std::env::var(format!("{}_SUCCESS", env)) {
+        Ok(raw) => {
+            let hex = raw.trim_start_matches('$').trim_start_matches("0x");
+            let pc = u16::from_str_radix(hex, 16).expect("success address should be hex");
+            harness.with_success_pc(pc)
+        }
+        Err(_) => harness,
+    };
+
+    let outcome = harness.run_path(path).expect("test binary should load");
+    assert!(outcome.passed(), "{}: {}", path, outcome.describe());
+}
+
+#[test]
+fn klaus_6502_functional_test_passes() {
+    run_binary(
+        NMOS_ENV,
+        FunctionalTest::klaus_6502().with_variant(CpuVariant::Cmos65C02),
+    );
+}
+
+#[test]
+fn klaus_6502_functional_test_passes_on_the_nmos_core() {
+    run_binary(NMOS_ENV, FunctionalTest::klaus_6502());
+}
+
+#[test]
+fn klaus_65c02_extended_opcodes_test_passes() {
+    run_binary(EXTENDED_ENV, FunctionalTest::klaus_65c02_extended());
+}
+
+#[test]
+fn harness_reports_success_and_the_failing_test_case() {
+    let program = assemble(
+        0x0400,
+        "
+        test_case = $0200
+                lda #1
+                sta test_case
+                lda #$40
+                cmp #$40
+                bne *           ; test 1 fails here
+                inc test_case
+                sec
+                bcc *           ; test 2 fails here
+        done:   jmp done
+        ",
+    )
+    .expect("program should assemble");
+    let mut image = vec![0u8; 0x0400];
+    image.extend_from_slice(&program.bytes);
+
+    let harness = FunctionalTest::klaus_6502().with_success_pc(program.labels["done"]);
+    let outcome = harness.run(&image).expect("image fits");
+    assert!(outcome.passed(), "{}", outcome.describe());
+
+    // Flip SEC to CLC so test 2 traps on its BCC.
+    let sec = image.iter().rposition(|b| *b == 0x38).expect("SEC present");
+    image[sec] = 0x18;
+    let outcome = harness.run(&image).expect("image fits");
+    assert!(matches!(
+        outcome,
+        FunctionalOutcome::Trapped {
+            pc: 0x040f,
+            test_case: 2,
+            ..
+        }
+    ));
+    assert!(
+        outcome
+            .describe()
+            .starts_with("trapped at $040F in test case $02")
+    );
+
+    let mut endless = FunctionalTest::klaus_6502().with_success_pc(0xffff);
+    endless.max_cycles = 1_000;
+    let outcome = endless.run(&[0xea; 0x10000]).expect("image fits");
+    assert!(matches!(outcome, FunctionalOutcome::TimedOut { .. }));
+}
@@ -0,0 +1,118 @@
+use echo_lab::cpu::asm::assemble;
+use echo_lab::machines::iie;
+use echo_lab::trace::{TraceFormat, Tracer, diff_traces};
+use std::fs;
+use std::path::PathBuf;
+
+// Set ECHOLAB_BLESS=1 to rewrite the golden files after an intended change.
+const BLESS_ENV: &str = "ECHOLAB_BLESS";
+const CONTEXT_LINES: usize = 5;
+
+fn check_golden(name: &str, source: &str, cycles: u64) {
+    let program = assemble(0x0300, source).expect("program should assemble");
+    let mut machine = iie::apple_iie();
+    machine.power_on();
+    machine.memory.main_mut()[0x0300..program.end() as usize].copy_from_slice(&program.bytes);
+    machine.cpu.pc = 0x0300;
+
+    let mut tracer = Tracer::in_memory(TraceFormat::Native);
+    tracer
+        .run_cycles(&mut machine, cycles)
+        .expect("memory sink");
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{}.trace", name));
+    if std::env::var_os(BLESS_ENV).is_some() {
+        let mut text = tracer.lines().join("\n");
+        text.push('\n');
+        fs::write(&path, text).expect("golden trace should be written");
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "missing golden trace {} ({}); run with {}=1 to create it",
+            path.display(),
+            e,
+            BLESS_ENV
+        )
+    });
+    if let Err(report) = diff_traces(&expected, tracer.lines(), CONTEXT_LINES) {
+        panic!("{}: {}", path.display(), report);
+    }
+}
+
+#[test]
+fn cpu_arithmetic_stack_and_branches_match_golden_trace() {
+    check_golden(
+        "cpu_basics",
+        "
+                ldx #5
+                lda #$00
+                clc
+        sum:    adc #$11
+                dex
+                bne sum
+                sed
+                sec
+                sbc #$09
+                cld
+                pha
+                jsr double
+                plx
+                ldy #$ff
+                sty $20
+                lsr $20
+                ror a
+                bit $20
+                bra *
+        double: asl a
+                rts
+        ",
+        120,
+    );
+}
+
+#[test]
+fn iie_soft_switches_match_golden_trace() {
+    check_golden(
+        "iie_soft_switches",
+        "
+                sta $c005       ; RAMWRT on
+                lda #$a5
+                sta $0800
+                lda $c014       ; RAMWRT status
+                sta $c004
+                lda $0800       ; main RAM still zero
+                lda $c055       ; PAGE2
+                lda $c01c
+                lda $c054
+                lda $c08b       ; language card: read RAM bank 1,
+                lda $c08b       ; second read enables writes
+                lda #$5a
+                sta $d000
+                lda $d000
+                lda $c012
+                lda $c082       ; back to ROM
+                lda $d000
+        wait:   bra wait
+        ",
+        80,
+    );
+}
+
+#[test]
+fn divergence_report_points_at_first_mismatch_with_context() {
+    let expected = "a\nb\nc\nd\n";
+    let actual: Vec<String> = ["a", "b", "x", "d"].map(str::to_owned).to_vec();
+    let report = diff_traces(expected, &actual, 1).expect_err("traces differ");
+    assert_eq!(
+        report,
+        "trace diverges at line 3 (expected 4 lines, got 4)\n       2  b\n-      3  c\n+      3  x"
+    );
+
+    let report = diff_traces(expected, &actual[..2], 0).expect_err("trace is short");
+    assert!(report.ends_with("+      3  <end of trace>"));
+    assert!(diff_traces("a\nb\n", &actual[..2], 3).is_ok());
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use common::{SETTLED, iie_with, row, set_switch};
+use echo_lab::machines::iie::memory::IieMemory;
+use echo_lab::screen_buffer::ScreenBuffer;
+use echo_lab::video::hires::{
+    HIRES_BLUE, HIRES_GREEN, HIRES_ORANGE, HIRES_VIOLET, HIRES_WHITE, HiresColor, row_address,
+};
+use echo_lab::video::scanner::CYCLES_PER_FRAME;
+use echo_lab::video::{COLOR_BLACK, COLOR_PHOSPHOR_GREEN, TextVideoController};
+
+// TEXT off, HIRES on.
+const HIRES: [u16; 2] = [0xc050, 0xc057];
+
+fn render(mem: &IieMemory, cycle: u64, model: HiresColor) -> ScreenBuffer {
+    common::render(
+        mem,
+        cycle,
+        TextVideoController::default().with_hires_color(model),
+    )
+}
+
+// Fills hi-res line 0 by repeating a pair of bytes.
+fn fill_line(mem: &mut IieMemory, even: u8, odd: u8) {
+    for col in 0..40 {
+        mem.main_mut()[0x2000 + col] = if col % 2 == 0 { even } else { odd };
+    }
+}
+
+#[test]
+fn hires_rows_interleave_by_eight_line_groups_and_thirds() {
+    assert_eq!(row_address(0x2000, 0), 0x2000);
+    assert_eq!(row_address(0x2000, 1), 0x2400);
+    assert_eq!(row_address(0x2000, 8), 0x2080);
+    assert_eq!(row_address(0x2000, 64), 0x2028);
+    assert_eq!(row_address(0x2000, 191), 0x3fd0);
+    assert_eq!(row_address(0x4000, 0), 0x4000);
+
+    let mut mem = iie_with(&HIRES);
+    mem.main_mut()[0x2400] = 0x7f;
+    mem.main_mut()[0x4000] = 0x7f;
+    let page1 = render(&mem, SETTLED, HiresColor::Monochrome);
+    assert_eq!(page1.get_pixel(0, 2), Some(COLOR_PHOSPHOR_GREEN));
+    assert_eq!(page1.get_pixel(0, 0), Some(COLOR_BLACK));
+
+    set_switch(&mut mem, 0xc055, 3 * CYCLES_PER_FRAME); // PAGE2
+    let page2 = render(&mem, 5 * CYCLES_PER_FRAME, HiresColor::Monochrome);
+    assert_eq!(page2.get_pixel(0, 0), Some(COLOR_PHOSPHOR_GREEN));
+    assert_eq!(page2.get_pixel(0, 2), Some(COLOR_BLACK));
+}
+
+#[test]
+fn palette_bit_delays_a_byte_by_half_a_pixel() {
+    let mut mem = iie_with(&HIRES);
+    mem.main_mut()[0x2000] = 0x01;
+    mem.main_mut()[0x2001] = 0x81;
+    // Bit 6 of the last undelayed byte is held into the delayed byte's first half-dot.
+    mem.main_mut()[0x2002] = 0x40;
+    mem.main_mut()[0x2003] = 0x80;
+    let frame = render(&mem, SETTLED, HiresColor::Monochrome);
+    let lit: Vec<usize> = row(&frame, 0)
+        .iter()
+        .enumerate()
+        .filter(|(_, color)| **color == COLOR_PHOSPHOR_GREEN)
+        .map(|(x, _)| x)
+        .collect();
+    assert_eq!(lit, vec![0, 1, 15, 16, 40, 41, 42]);
+}
+
+#[test]
+fn artifact_colors_follow_dot_patterns_and_the_palette_bit() {
+    let cases = [
+        (0x55, 0x2a, HIRES_VIOLET),
+        (0x2a, 0x55, HIRES_GREEN),
+        (0xd5, 0xaa, HIRES_BLUE),
+        (0xaa, 0xd5, HIRES_ORANGE),
+        (0x7f, 0x7f, HIRES_WHITE),
+        (0x00, 0x80, COLOR_BLACK),
+    ];
+    for (even, odd, color) in cases {
+        let mut mem = iie_with(&HIRES);
+        fill_line(&mut mem, even, odd);
+        let frame = render(&mem, SETTLED, HiresColor::Artifact);
+        let line = row(&frame, 0);
+        assert!(
+            line[140..420].iter().all(|pixel| *pixel == color),
+            "bytes {:02X}/{:02X}",
+            even,
+            odd
+        );
+    }
+}
+
+#[test]
+fn idealized_model_colors_lone_pixels_and_whitens_neighbors() {
+    let mut mem = iie_with(&HIRES);
+    mem.main_mut()[0x2000] = 0x01 | 0x08; // pixels 0 and 3
+    mem.main_mut()[0x2001] = 0x80 | 0x01 | 0x08 | 0x10; // pixels 7, 10, 11
+    let frame = render(&mem, SETTLED, HiresColor::Idealized);
+    let line = row(&frame, 0);
+    let pixel = |x: usize| line[x * 2];
+    assert_eq!(pixel(0), HIRES_VIOLET);
+    assert_eq!(pixel(3), HIRES_GREEN);
+    assert_eq!(pixel(1), COLOR_BLACK);
+    assert_eq!(pixel(7), HIRES_ORANGE);
+    assert_eq!(pixel(10), HIRES_WHITE);
+    assert_eq!(pixel(11), HIRES_WHITE);
+    // Idealized pixels ignore the half-dot shift.
+    assert_eq!(line[14], HIRES_ORANGE);
+    assert_eq!(line[15], HIRES_ORANGE);
+
+    assert_eq!(HiresColor::parse("Ideal"), Ok(HiresColor::Idealized));
+    assert_eq!(HiresColor::parse("mono"), Ok(HiresColor::Monochrome));
+    assert!(HiresColor::parse("sepia").is_err());
+}
+
+#[test]
+fn mixed_hires_shows_text_below_line_160() {
+    let mut mem = iie_with(&HIRES);
+    set_switch(&mut mem, 0xc053, 0); // MIXED on
+    mem.main_mut()[0x2000..0x4000].fill(0x7f);
+    mem.main_mut()[0x0400..0x0800].fill(b' ' | 0x80);
+    let frame = render(&mem, SETTLED, HiresColor::Artifact);
+    assert_eq!(frame.get_pixel(100, 159 * 2), Some(HIRES_WHITE));
+    assert_eq!(frame.get_pixel(100, 160 * 2), Some(COLOR_BLACK));
+}
@@ -0,0 +1,99 @@
+use echo_lab::bus::Bus;
+use echo_lab::machines::iie::memory::IieMemory;
+use echo_lab::screen_buffer::ScreenBuffer;
+use echo_lab::video::{
+    COLOR_BLACK, COLOR_PHOSPHOR_GREEN, FRAME_HEIGHT, FRAME_WIDTH, TextVideoController,
+};
+
+#[test]
+fn ramrd_and_ramwrt_route_main_and_aux_independently() {
+    let mut mem = IieMemory::new();
+    mem.write(0x2000, 0x11, 0);
+
+    mem.write(0xc005, 0, 0); // RAMWRT on
+    mem.write(0x2000, 0x22, 0);
+    assert_eq!(mem.read(0x2000, 0), 0x11);
+    assert_eq!(mem.aux()[0x2000], 0x22);
+
+    mem.write(0xc003, 0, 0); // RAMRD on
+    assert_eq!(mem.read(0x2000, 0), 0x22);
+    assert_eq!(mem.read(0xc013, 0) & 0x80, 0x80);
+    assert_eq!(mem.read(0xc014, 0) & 0x80, 0x80);
+
+    mem.write(0xc002, 0, 0);
+    mem.write(0xc004, 0, 0);
+    assert_eq!(mem.read(0x2000, 0), 0x11);
+    assert_eq!(mem.read(0xc013, 0) & 0x80, 0);
+}
+
+#[test]
+fn altzp_swaps_zero_page_and_stack_only() {
+    let mut mem = IieMemory::new();
+    mem.write(0x00ff, 0x01, 0);
+    mem.write(0x01ff, 0x02, 0);
+    mem.write(0x0200, 0x03, 0);
+
+    mem.write(0xc009, 0, 0); // ALTZP on
+    assert_eq!(mem.read(0x00ff, 0), 0x00);
+    mem.write(0x01ff, 0x44, 0);
+    mem.write(0x0200, 0x55, 0);
+    assert_eq!(mem.aux()[0x01ff], 0x44);
+    assert_eq!(mem.main()[0x01ff], 0x02);
+    assert_eq!(mem.main()[0x0200], 0x55);
+    assert_eq!(mem.read(0xc016, 0) & 0x80, 0x80);
+}
+
+#[test]
+fn store80_with_page2_banks_text_and_hires_pages() {
+    let mut mem = IieMemory::new();
+    mem.write(0xc001, 0, 0); // 80STORE on
+    mem.read(0xc055, 0); // PAGE2 on
+    mem.write(0x0400, 0xaa, 0);
+    mem.write(0x2000, 0xbb, 0);
+    assert_eq!(mem.aux()[0x0400], 0xaa);
+    assert_eq!(mem.main()[0x2000], 0xbb);
+
+    mem.read(0xc057, 0); // HIRES on
+    mem.write(0x2000, 0xcc, 0);
+    assert_eq!(mem.aux()[0x2000], 0xcc);
+
+    // RAMWRT is ignored for the text page while 80STORE is active.
+    mem.read(0xc054, 0);
+    mem.write(0xc005, 0, 0);
+    mem.write(0x0401, 0xdd, 0);
+    assert_eq!(mem.main()[0x0401], 0xdd);
+    assert_eq!(mem.read(0xc018, 0) & 0x80, 0x80);
+}
+
+#[test]
+fn status_reads_report_switch_state_in_bit_7() {
+    let mut mem = IieMemory::new();
+    assert_eq!(mem.read(0xc01f, 0) & 0x80, 0);
+    mem.write(0xc00d, 0, 0); // 80COL on
+    assert_eq!(mem.read(0xc01f, 0) & 0x80, 0x80);
+
+    mem.write(0xc007, 0, 0);
+    assert_eq!(mem.read(0xc015, 0) & 0x80, 0x80);
+    mem.write(0xc00b, 0, 0);
+    assert_eq!(mem.read(0xc017, 0) & 0x80, 0x80);
+    assert!(mem.mmu().intcxrom);
+    assert!(mem.mmu().slotc3rom);
+}
+
+#[test]
+fn text_video_reads_main_page_selected_by_page2_regardless_of_ramrd() {
+    let mut mem = IieMemory::new();
+    mem.main_mut()[0x0400] = b' ' | 0x80;
+    mem.main_mut()[0x0800] = b'H' | 0x80;
+    mem.aux_mut()[0x0800] = b' ' | 0x80;
+    mem.read(0xc055, 0); // PAGE2 on
+    mem.write(0xc003, 0, 0); // RAMRD on
+
+    let mut out = ScreenBuffer::new(FRAME_WIDTH, FRAME_HEIGHT);
+    TextVideoController::default().render_frame(&mem, 0, &mut out);
+    assert_eq!(out.get_pixel(2, 6), Some(COLOR_PHOSPHOR_GREEN));
+
+    mem.read(0xc054, 0);
+    TextVideoController::default().render_frame(&mem, 0, &mut out);
+    assert_eq!(out.get_pixel(2, 6), Some(COLOR_BLACK));
+}
@@ -0,0 +1,75 @@
+use echo_lab::bus::Bus;
+use echo_lab::machines::iie::memory::IieMemory;
+use echo_lab::machines::iie::mmu::Bank;
+
+#[test]
+fn power_on_reads_rom_and_writes_bank2_ram() {
+    let mut mem = IieMemory::new();
+    let lc = mem.language_card();
+    assert!(!lc.read_ram && lc.write_enabled && lc.bank2);
+
+    mem.write(0xd000, 0x42, 0);
+    mem.write(0xf000, 0x43, 0);
+    assert_eq!(mem.read(0xd000, 0), 0x00);
+
+    mem.read(0xc080, 0); // read RAM bank 2, no write
+    assert_eq!(mem.read(0xd000, 0), 0x42);
+    assert_eq!(mem.read(0xf000, 0), 0x43);
+    assert_eq!(mem.read(0xc011, 0) & 0x80, 0x80);
+    assert_eq!(mem.read(0xc012, 0) & 0x80, 0x80);
+}
+
+#[test]
+fn write_enable_requires_two_consecutive_odd_reads() {
+    let mut mem = IieMemory::new();
+    mem.read(0xc088, 0); // read RAM bank 1, write protected
+    assert!(!mem.language_card().write_enabled);
+    assert!(!mem.language_card().bank2);
+    assert_eq!(mem.read(0xc011, 0) & 0x80, 0);
+
+    mem.read(0xc08b, 0);
+    assert!(!mem.language_card().write_enabled);
+    mem.read(0xc08b, 0);
+    assert!(mem.language_card().write_enabled);
+
+    mem.write(0xd123, 0x99, 0);
+    assert_eq!(mem.read(0xd123, 0), 0x99);
+    assert_eq!(mem.language_card_bank1(Bank::Main)[0x0123], 0x99);
+    assert_eq!(mem.main()[0xd123], 0x00);
+}
+
+#[test]
+fn odd_write_interrupts_the_double_read_sequence() {
+    let mut mem = IieMemory::new();
+    mem.read(0xc082, 0); // read ROM, write protected
+    assert!(!mem.language_card().write_enabled);
+
+    mem.read(0xc081, 0);
+    mem.write(0xc081, 0, 0);
+    mem.read(0xc081, 0);
+    assert!(!mem.language_card().write_enabled);
+    mem.read(0xc081, 0);
+    assert!(mem.language_card().write_enabled);
+    assert!(!mem.language_card().read_ram);
+
+    // Read ROM, write RAM.
+    mem.write(0xe000, 0x5a, 0);
+    assert_eq!(mem.read(0xe000, 0), 0x00);
+    assert_eq!(mem.main()[0xe000], 0x5a);
+}
+
+#[test]
+fn altzp_selects_aux_language_card_ram() {
+    let mut mem = IieMemory::new();
+    mem.read(0xc08b, 0);
+    mem.read(0xc08b, 0);
+    mem.write(0xd000, 0x11, 0);
+
+    mem.write(0xc009, 0, 0); // ALTZP on
+    assert_eq!(mem.read(0xd000, 0), 0x00);
+    mem.write(0xd000, 0x22, 0);
+    assert_eq!(mem.language_card_bank1(Bank::Aux)[0], 0x22);
+
+    mem.write(0xc008, 0, 0);
+    assert_eq!(mem.read(0xd000, 0), 0x11);
+}
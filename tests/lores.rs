@@ -0,0 +1,94 @@
+mod common;
+
+use common::{SETTLED, iie_with, set_switch, y};
+use echo_lab::machines::iie::memory::IieMemory;
+use echo_lab::screen_buffer::ScreenBuffer;
+use echo_lab::video::scanner::CYCLES_PER_FRAME;
+use echo_lab::video::{
+    CELL_WIDTH, COLOR_BLACK, COLOR_PHOSPHOR_GREEN, LORES_PALETTE, TextVideoController,
+};
+
+fn render(mem: &IieMemory, cycle: u64) -> ScreenBuffer {
+    common::render(mem, cycle, TextVideoController::default())
+}
+
+#[test]
+fn lores_blocks_take_the_low_nibble_on_top_and_the_high_nibble_below() {
+    let mut mem = iie_with(&[0xc050]); // TEXT off
+    mem.main_mut()[0x0400] = 0x1f;
+    mem.main_mut()[0x0401] = 0x9d;
+    mem.main_mut()[0x0480] = 0x06;
+
+    let frame = render(&mem, SETTLED);
+    for line in 0..4 {
+        assert_eq!(frame.get_pixel(0, y(line)), Some(LORES_PALETTE[0xf]));
+        assert_eq!(
+            frame.get_pixel(CELL_WIDTH - 1, y(line)),
+            Some(LORES_PALETTE[0xf])
+        );
+        assert_eq!(
+            frame.get_pixel(CELL_WIDTH, y(line)),
+            Some(LORES_PALETTE[0xd])
+        );
+    }
+    for line in 4..8 {
+        assert_eq!(frame.get_pixel(0, y(line)), Some(LORES_PALETTE[0x1]));
+        assert_eq!(
+            frame.get_pixel(CELL_WIDTH, y(line)),
+            Some(LORES_PALETTE[0x9])
+        );
+    }
+    // Row 1 lives at $480; odd output rows stay black.
+    assert_eq!(frame.get_pixel(0, y(8)), Some(LORES_PALETTE[0x6]));
+    assert_eq!(frame.get_pixel(0, y(12)), Some(COLOR_BLACK));
+    assert_eq!(frame.get_pixel(0, y(8) + 1), Some(COLOR_BLACK));
+}
+
+#[test]
+fn palette_has_sixteen_distinct_opaque_colors() {
+    for (i, color) in LORES_PALETTE.iter().enumerate() {
+        assert_eq!(color >> 24, 0xff, "entry {}", i);
+        assert!(!LORES_PALETTE[..i].contains(color), "entry {} repeats", i);
+    }
+    assert_eq!(LORES_PALETTE[0], COLOR_BLACK);
+    assert_eq!(LORES_PALETTE[15], 0xffff_ffff);
+}
+
+#[test]
+fn mixed_mode_keeps_four_text_rows_at_the_bottom() {
+    let mut mem = iie_with(&[0xc050, 0xc053]); // TEXT off, MIXED on
+    // Normal 'H' ($C8): lo-res brown over light green.
+    mem.main_mut()[0x0400..0x0800].fill(b'H' | 0x80);
+
+    let frame = render(&mem, SETTLED);
+    assert_eq!(frame.get_pixel(0, y(159)), Some(LORES_PALETTE[0xc]));
+    assert_eq!(frame.get_pixel(0, y(155)), Some(LORES_PALETTE[0x8]));
+
+    // 'H' row 3 is its crossbar; text uses the foreground color.
+    let text_row = (0..CELL_WIDTH).map(|x| frame.get_pixel(x, y(160 + 3)));
+    assert!(text_row.clone().any(|p| p == Some(COLOR_PHOSPHOR_GREEN)));
+    assert!(
+        text_row
+            .clone()
+            .all(|p| p == Some(COLOR_PHOSPHOR_GREEN) || p == Some(COLOR_BLACK))
+    );
+    assert_eq!(frame.get_pixel(0, y(191)), Some(COLOR_BLACK));
+
+    // Without MIXED the bottom rows are graphics too.
+    set_switch(&mut mem, 0xc052, SETTLED + 1);
+    let frame = render(&mem, 4 * CYCLES_PER_FRAME);
+    assert_eq!(frame.get_pixel(0, y(163)), Some(LORES_PALETTE[0x8]));
+}
+
+#[test]
+fn switching_to_graphics_mid_frame_splits_text_and_lores() {
+    let mut mem = IieMemory::new();
+    mem.main_mut()[0x0400..0x0800].fill(0x20); // inverse space: solid in text
+    // Drop TEXT during line 95's blanking, ahead of row 12.
+    set_switch(&mut mem, 0xc050, CYCLES_PER_FRAME + 95 * 65 + 50);
+
+    let frame = render(&mem, 2 * CYCLES_PER_FRAME - 1);
+    assert_eq!(frame.get_pixel(0, y(95)), Some(COLOR_PHOSPHOR_GREEN));
+    assert_eq!(frame.get_pixel(0, y(96)), Some(LORES_PALETTE[0x0]));
+    assert_eq!(frame.get_pixel(0, y(100)), Some(LORES_PALETTE[0x2]));
+}
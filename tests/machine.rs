@@ -0,0 +1,102 @@
+use echo_lab::bus::Bus;
+use echo_lab::cpu::{FLAG_B, FLAG_D, FLAG_I};
+use echo_lab::machines::Machine;
+use echo_lab::machines::iie::apple_iie;
+use echo_lab::rom::SystemRom;
+
+// ROM image with RESET -> $F000, IRQ -> $F100, NMI -> $F200; each handler is an RTI
+// preceded by a NOP so handler entry is observable.
+fn machine_with_vectors() -> Machine {
+    let mut image = vec![0xeau8; 0x4000];
+    let put = |image: &mut Vec<u8>, addr: u16, bytes: &[u8]| {
+        let offset = (addr - 0xc000) as usize;
+        image[offset..offset + bytes.len()].copy_from_slice(bytes);
+    };
+    put(&mut image, 0xf100, &[0xea, 0x40]);
+    put(&mut image, 0xf200, &[0xea, 0x40]);
+    put(&mut image, 0xfffa, &[0x00, 0xf2, 0x00, 0xf0, 0x00, 0xf1]);
+
+    let mut machine = apple_iie();
+    machine.load_rom(&SystemRom::from_bytes(image).expect("rom should parse"));
+    machine
+}
+
+#[test]
+fn power_on_fetches_reset_vector_and_masks_interrupts() {
+    let mut machine = machine_with_vectors();
+    machine.cpu.p |= FLAG_D;
+
+    assert_eq!(machine.power_on(), 7);
+    assert_eq!(machine.cpu.pc, 0xf000);
+    assert_eq!(machine.cpu.sp, 0xfa);
+    assert!(machine.cpu.flag(FLAG_I));
+    assert!(!machine.cpu.flag(FLAG_D));
+    assert_eq!(machine.cpu.cycles, 7);
+}
+
+#[test]
+fn warm_reset_preserves_ram_but_clears_soft_switches() {
+    let mut machine = machine_with_vectors();
+    machine.power_on();
+    machine.write(0x0300, 0x5a, 0);
+    machine.write(0xc005, 0, 0); // RAMWRT on
+    machine.read(0xc08b, 0);
+    machine.read(0xc08b, 0);
+    machine.cpu.pc = 0x1234;
+
+    machine.reset();
+    assert_eq!(machine.cpu.pc, 0xf000);
+    assert_eq!(machine.read(0x0300, 0), 0x5a);
+    assert!(!machine.memory.mmu().ramwrt);
+    assert!(!machine.memory.language_card().read_ram);
+}
+
+#[test]
+fn irq_is_level_triggered_and_masked_by_i_flag() {
+    let mut machine = machine_with_vectors();
+    machine.power_on();
+
+    machine.set_irq(3, true);
+    machine.step();
+    assert_eq!(machine.cpu.pc, 0xf001, "I flag should mask IRQ");
+
+    machine.cpu.p &= !FLAG_I;
+    assert_eq!(machine.step(), 7);
+    assert_eq!(machine.cpu.pc, 0xf100);
+    let pushed_p = machine.peek(0x0100 | machine.cpu.sp.wrapping_add(1) as u16);
+    assert_eq!(pushed_p & FLAG_B, 0, "hardware interrupts push B clear");
+
+    machine.step(); // NOP
+    machine.step(); // RTI restores I clear
+    assert_eq!(machine.cpu.pc, 0xf001);
+    assert_eq!(machine.step(), 7, "still-asserted line re-enters");
+    assert_eq!(machine.cpu.pc, 0xf100);
+
+    machine.step();
+    machine.step();
+    machine.set_irq(3, false);
+    machine.step();
+    assert_eq!(machine.cpu.pc, 0xf002);
+}
+
+#[test]
+fn nmi_fires_once_per_rising_edge() {
+    let mut machine = machine_with_vectors();
+    machine.power_on();
+
+    machine.set_nmi(true);
+    assert_eq!(machine.step(), 7);
+    assert_eq!(machine.cpu.pc, 0xf200);
+    machine.step();
+    machine.step();
+    assert_eq!(machine.cpu.pc, 0xf000);
+
+    // Holding the line high does not retrigger.
+    machine.step();
+    assert_eq!(machine.cpu.pc, 0xf001);
+
+    machine.set_nmi(false);
+    machine.set_nmi(true);
+    machine.step();
+    assert_eq!(machine.cpu.pc, 0xf200);
+}
@@ -0,0 +1,123 @@
+use echo_lab::bus::Bus;
+use echo_lab::machines::{Machine, iie};
+use echo_lab::monitor::{Breakpoint, Monitor, StopReason};
+use std::io::Cursor;
+
+fn machine_with(program: &[u8], origin: u16) -> Machine {
+    let mut machine = iie::apple_iie();
+    machine.power_on();
+    let start = origin as usize;
+    machine.memory.main_mut()[start..start + program.len()].copy_from_slice(program);
+    machine.cpu.pc = origin;
+    machine
+}
+
+fn run(monitor: &mut Monitor, machine: &mut Machine, line: &str) -> String {
+    monitor
+        .execute(machine, line)
+        .unwrap_or_else(|e| panic!("'{}' failed: {}", line, e))
+        .text
+}
+
+#[test]
+fn step_and_step_over_report_instructions_and_registers() {
+    // $0300: JSR $0310; LDX #$02 / $0310: LDA #$01; RTS
+    let mut machine = machine_with(&[0x20, 0x10, 0x03, 0xa2, 0x02], 0x0300);
+    machine.memory.main_mut()[0x0310..0x0313].copy_from_slice(&[0xa9, 0x01, 0x60]);
+    let mut monitor = Monitor::new();
+
+    let text = run(&mut monitor, &mut machine, "n");
+    assert_eq!(machine.cpu.pc, 0x0303);
+    assert_eq!(machine.cpu.a, 0x01);
+    assert!(text.contains("PC=0303 A=01"));
+
+    let text = run(&mut monitor, &mut machine, "s");
+    assert!(text.starts_with("0303: A2 02     LDX"));
+    assert_eq!(machine.cpu.x, 0x02);
+}
+
+#[test]
+fn registers_and_memory_can_be_edited_dumped_and_searched() {
+    let mut machine = machine_with(&[0xea], 0x0300);
+    let mut monitor = Monitor::new();
+
+    let text = run(&mut monitor, &mut machine, "r a=41 x=$10 pc=0400 p=30");
+    assert!(text.starts_with("PC=0400 A=41 X=10"));
+    assert_eq!(machine.cpu.p, 0x30);
+
+    run(&mut monitor, &mut machine, "f 2000 2007 c1");
+    assert_eq!(machine.peek(0x2007), 0xc1);
+    assert_eq!(machine.peek(0x2008), 0x00);
+    let err = monitor
+        .execute(&mut machine, "f 2008 2000 ff")
+        .expect_err("reversed range");
+    assert!(err.starts_with("usage: f"));
+    assert_eq!(machine.peek(0x2000), 0xc1);
+
+    let text = run(&mut monitor, &mut machine, "m 2000 8");
+    assert_eq!(text, format!("2000: {:<47}  AAAAAAAA", ["C1"; 8].join(" ")));
+
+    machine.memory.main_mut()[0x2100..0x2102].copy_from_slice(&[0xde, 0xad]);
+    let text = run(&mut monitor, &mut machine, "find 2000 2fff de ad");
+    assert_eq!(text, "$2100");
+
+    let text = run(&mut monitor, &mut machine, "a 0400 lda ($3c),y");
+    assert_eq!(text, "0400: B1 3C     LDA ($3C),Y");
+    let text = run(&mut monitor, &mut machine, "d 0400 2");
+    assert_eq!(text, "0400: B1 3C     LDA ($3C),Y\n0402: 00        BRK");
+
+    assert!(monitor.execute(&mut machine, "r q=1").is_err());
+    assert!(monitor.execute(&mut machine, "bogus").is_err());
+}
+
+#[test]
+fn address_opcode_and_cycle_breakpoints_stop_execution() {
+    // $0300: INX; INX; INY; JMP $0300
+    let mut machine = machine_with(&[0xe8, 0xe8, 0xc8, 0x4c, 0x00, 0x03], 0x0300);
+    let mut monitor = Monitor::new().with_run_budget(1_000);
+
+    run(&mut monitor, &mut machine, "b 0302");
+    let text = run(&mut monitor, &mut machine, "g");
+    assert!(text.starts_with("hit address $0302"));
+    assert_eq!(machine.cpu.x, 2);
+
+    run(&mut monitor, &mut machine, "bc");
+    run(&mut monitor, &mut machine, "b op 4c");
+    assert_eq!(
+        monitor.run(&mut machine, 1_000),
+        StopReason::Breakpoint(Breakpoint::Opcode(0x4c))
+    );
+    assert_eq!(machine.cpu.pc, 0x0303);
+
+    run(&mut monitor, &mut machine, "bc 0");
+    let target = machine.cpu.cycles + 50;
+    run(&mut monitor, &mut machine, &format!("b cycle {}", target));
+    assert_eq!(
+        monitor.run(&mut machine, 1_000),
+        StopReason::Breakpoint(Breakpoint::Cycle(target))
+    );
+    assert!(machine.cpu.cycles >= target && machine.cpu.cycles < target + 4);
+    assert!(monitor.breakpoints().is_empty());
+
+    assert_eq!(monitor.run(&mut machine, 100), StopReason::Budget);
+}
+
+#[test]
+fn repl_reads_commands_until_quit() {
+    let mut machine = machine_with(&[0xa9, 0x42, 0xea, 0xea], 0x0300);
+    let mut monitor = Monitor::new();
+    let mut output = Vec::new();
+
+    monitor
+        .run_repl(
+            &mut machine,
+            Cursor::new("t 0303\nnope\nq\ns\n"),
+            &mut output,
+        )
+        .expect("repl should run");
+
+    let output = String::from_utf8(output).expect("utf-8 output");
+    assert!(output.contains("PC=0303 A=42"));
+    assert!(output.contains("error: unknown command 'nope'"));
+    assert_eq!(machine.cpu.pc, 0x0303);
+}
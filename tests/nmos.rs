@@ -0,0 +1,158 @@
+use echo_lab::bus::Bus;
+use echo_lab::cpu::asm::Assembler;
+use echo_lab::cpu::{Cpu, CpuVariant, FLAG_C, FLAG_D, FLAG_N, FLAG_Z};
+use echo_lab::machines::MachineKind;
+use echo_lab::machines::ii_plus;
+use echo_lab::trace::{TraceFormat, trace_step};
+
+fn load_nmos(source: &str) -> (Cpu, [u8; 65536]) {
+    let program = Assembler::new()
+        .with_variant(CpuVariant::Nmos6502)
+        .assemble(0x0300, source)
+        .expect("program should assemble");
+    let mut mem = [0u8; 65536];
+    mem[0x0300..program.end() as usize].copy_from_slice(&program.bytes);
+    let mut cpu = Cpu::new().with_variant(CpuVariant::Nmos6502);
+    cpu.pc = 0x0300;
+    (cpu, mem)
+}
+
+fn run(cpu: &mut Cpu, mem: &mut [u8; 65536], steps: usize) {
+    for _ in 0..steps {
+        cpu.step(mem);
+    }
+}
+
+#[test]
+fn undocumented_opcodes_combine_their_halves() {
+    let (mut cpu, mut mem) = load_nmos(
+        "
+        lax $10         ; A = X = $81
+        sax $11         ; $81 & $81
+        dcp $12         ; $82 -> $81, compare equal
+        isc $13         ; $00 -> $01, A = $81 - $01
+        slo $14         ; $40 -> $80, A |= $80
+        anc #$80        ; C copies bit 7
+        sbx #$01        ; X = (A & X) - 1
+        ",
+    );
+    mem[0x10] = 0x81;
+    mem[0x12] = 0x82;
+    mem[0x14] = 0x40;
+
+    run(&mut cpu, &mut mem, 3);
+    assert_eq!((cpu.a, cpu.x), (0x81, 0x81));
+    assert_eq!(mem[0x11], 0x81);
+    assert_eq!(mem[0x12], 0x81);
+    assert!(cpu.flag(FLAG_Z) && cpu.flag(FLAG_C));
+
+    run(&mut cpu, &mut mem, 1);
+    assert_eq!(mem[0x13], 0x01);
+    assert_eq!(cpu.a, 0x80);
+
+    run(&mut cpu, &mut mem, 3);
+    assert_eq!(mem[0x14], 0x80);
+    assert_eq!(cpu.a, 0x80);
+    assert!(cpu.flag(FLAG_C));
+    assert_eq!(cpu.x, 0x7f);
+    assert_eq!(cpu.cycles, 3 + 3 + 5 + 5 + 5 + 2 + 2);
+}
+
+#[test]
+fn decimal_mode_takes_flags_from_the_binary_result_on_nmos() {
+    let source = "
+        sed
+        clc
+        lda #$99
+        adc #$01
+        ";
+    let (mut nmos, mut mem) = load_nmos(source);
+    run(&mut nmos, &mut mem, 3);
+    assert_eq!(nmos.step(&mut mem), 2);
+    assert_eq!(nmos.a, 0x00);
+    assert!(nmos.flag(FLAG_C));
+    assert!(!nmos.flag(FLAG_Z), "Z follows the binary sum $9A");
+    assert!(nmos.flag(FLAG_N));
+
+    let mut cmos = Cpu::new();
+    cmos.pc = 0x0300;
+    run(&mut cmos, &mut mem, 3);
+    assert_eq!(cmos.step(&mut mem), 3);
+    assert_eq!(cmos.a, 0x00);
+    assert!(cmos.flag(FLAG_Z) && !cmos.flag(FLAG_N));
+
+    // BRK leaves D alone on the NMOS part.
+    mem[0xfffe..].copy_from_slice(&[0x00, 0x04]);
+    mem[nmos.pc as usize] = 0x00;
+    nmos.step(&mut mem);
+    assert_eq!(nmos.pc, 0x0400);
+    assert!(nmos.flag(FLAG_D));
+}
+
+#[test]
+fn indirect_jmp_wraps_within_the_page_only_on_nmos() {
+    let (mut nmos, mut mem) = load_nmos("jmp ($10ff)");
+    mem[0x10ff] = 0x34;
+    mem[0x1000] = 0x12;
+    mem[0x1100] = 0x56;
+
+    assert_eq!(nmos.step(&mut mem), 5);
+    assert_eq!(nmos.pc, 0x1234);
+
+    let mut cmos = Cpu::new();
+    cmos.pc = 0x0300;
+    assert_eq!(cmos.step(&mut mem), 6);
+    assert_eq!(cmos.pc, 0x5634);
+}
+
+#[test]
+fn rmw_writes_twice_and_jam_halts_until_reset() {
+    let (mut cpu, mut mem) = load_nmos(
+        "
+        inc $10
+        lax ($20),y
+        jam
+        ",
+    );
+    mem[0x10] = 0x41;
+
+    let (record, cycles) = trace_step(&mut cpu, &mut mem);
+    assert_eq!(cycles, 5);
+    let line = record.format(TraceFormat::Native);
+    assert!(line.contains("INC $10"), "{}", line);
+    assert!(line.ends_with("r0010:41 w0010:41 w0010:42"), "{}", line);
+
+    let (record, _) = trace_step(&mut cpu, &mut mem);
+    assert!(record.format(TraceFormat::Native).contains("LAX ($20),Y"));
+
+    assert_eq!(cpu.step(&mut mem), 2);
+    assert!(cpu.jammed());
+    let pc = cpu.pc;
+    assert_eq!(cpu.step(&mut mem), 1);
+    assert_eq!(cpu.pc, pc);
+
+    mem[0xfffc..0xfffe].copy_from_slice(&[0x00, 0x03]);
+    cpu.reset(&mut mem);
+    assert!(!cpu.jammed());
+    assert_eq!(cpu.pc, 0x0300);
+}
+
+#[test]
+fn ii_plus_machine_has_no_iie_switches_or_lowercase() {
+    let mut machine = ii_plus::apple_ii_plus();
+    machine.power_on();
+    assert_eq!(machine.cpu.variant(), CpuVariant::Nmos6502);
+    assert_eq!(MachineKind::parse("iiplus").unwrap(), MachineKind::IiPlus);
+    assert!(MachineKind::parse("iic").is_err());
+
+    // RAMWRT does not exist: the write lands in main memory.
+    machine.write(0xc005, 0x00, 0);
+    machine.write(0x0800, 0xa5, 0);
+    assert_eq!(machine.memory.main()[0x0800], 0xa5);
+    assert_eq!(machine.read(0xc014, 0), 0x00);
+
+    machine.memory.press_key(b'a');
+    assert_eq!(machine.read(0xc000, 0), b'A' | 0x80);
+    machine.read(0xc018, 0);
+    assert_eq!(machine.read(0xc000, 0), b'A');
+}
@@ -0,0 +1,60 @@
+use echo_lab::bus::Bus;
+use echo_lab::machines::iie::apple_iie;
+use echo_lab::machines::iie::mmu::Bank;
+use echo_lab::machines::ram_init::RamInit;
+
+#[test]
+fn fixed_patterns_fill_main_and_aux() {
+    let machine = apple_iie().with_ram_init(RamInit::Ones);
+    assert!(machine.memory.main().iter().all(|b| *b == 0xff));
+    assert!(machine.memory.aux().iter().all(|b| *b == 0xff));
+
+    let machine = apple_iie().with_ram_init(RamInit::IieStripes);
+    assert_eq!(
+        &machine.memory.main()[0x0400..0x0408],
+        &[0xff, 0xff, 0x00, 0x00, 0xff, 0xff, 0x00, 0x00]
+    );
+    assert_eq!(
+        &machine.memory.aux()[0x2000..0x2004],
+        &[0xff, 0xff, 0x00, 0x00]
+    );
+    assert_eq!(machine.memory.language_card_bank1(Bank::Aux)[2], 0x00);
+
+    let machine = apple_iie();
+    assert!(machine.memory.main().iter().all(|b| *b == 0x00));
+}
+
+#[test]
+fn random_pattern_is_reproducible_per_seed() {
+    let a = apple_iie().with_ram_init(RamInit::Random(42));
+    let b = apple_iie().with_ram_init(RamInit::Random(42));
+    let c = apple_iie().with_ram_init(RamInit::Random(43));
+
+    assert_eq!(a.memory.main(), b.memory.main());
+    assert_eq!(a.memory.aux(), b.memory.aux());
+    assert_ne!(a.memory.main(), c.memory.main());
+    assert_ne!(a.memory.main(), a.memory.aux());
+}
+
+#[test]
+fn power_on_reapplies_pattern_but_warm_reset_keeps_ram() {
+    let mut machine = apple_iie().with_ram_init(RamInit::Random(7));
+    let snapshot = *machine.memory.main();
+
+    machine.write(0x0300, !snapshot[0x0300], 0);
+    machine.reset();
+    assert_eq!(machine.peek(0x0300), !snapshot[0x0300]);
+
+    machine.power_on();
+    assert_eq!(machine.memory.main(), &snapshot);
+}
+
+#[test]
+fn parse_accepts_named_patterns_and_seeds() {
+    assert_eq!(RamInit::parse("zeros"), Ok(RamInit::Zeros));
+    assert_eq!(RamInit::parse("ones"), Ok(RamInit::Ones));
+    assert_eq!(RamInit::parse("iie"), Ok(RamInit::IieStripes));
+    assert_eq!(RamInit::parse("random:1_000"), Ok(RamInit::Random(1000)));
+    assert_eq!(RamInit::parse("random:0xBEEF"), Ok(RamInit::Random(0xbeef)));
+    assert!(RamInit::parse("random:nope").is_err());
+}
@@ -0,0 +1,109 @@
+use echo_lab::bus::Bus;
+use echo_lab::machines::iie::memory::IieMemory;
+use echo_lab::screen_buffer::ScreenBuffer;
+use echo_lab::video::log::VideoEvent;
+use echo_lab::video::scanner::CYCLES_PER_FRAME;
+use echo_lab::video::{CELL_HEIGHT, FRAME_HEIGHT, FRAME_WIDTH, TextVideoController};
+
+fn cycle_at(frame: u64, line: u64, column: u64) -> u64 {
+    frame * CYCLES_PER_FRAME + line * 65 + column
+}
+
+fn render<B: Bus>(bus: &B, cycle: u64) -> ScreenBuffer {
+    let mut out = ScreenBuffer::new(FRAME_WIDTH, FRAME_HEIGHT);
+    TextVideoController::default().render_frame(bus, cycle, &mut out);
+    out
+}
+
+fn text_row(frame: &ScreenBuffer, row: usize) -> &[u32] {
+    let start = row * CELL_HEIGHT * FRAME_WIDTH;
+    &frame.pixels()[start..start + CELL_HEIGHT * FRAME_WIDTH]
+}
+
+// Page 1 full of 'A', page 2 full of 'B'; the screen starts on page 1.
+fn two_pages() -> IieMemory {
+    let mut mem = IieMemory::new();
+    mem.main_mut()[0x0400..0x0800].fill(b'A' | 0x80);
+    mem.main_mut()[0x0800..0x0c00].fill(b'B' | 0x80);
+    mem
+}
+
+#[test]
+fn page_flip_mid_frame_splits_the_screen_at_the_beam() {
+    let page1 = render(&two_pages(), 0);
+    let mut flipped = two_pages();
+    flipped.read(0xc055, 0);
+    let page2 = render(&flipped, 0);
+
+    // Flip during line 95's horizontal blanking, ahead of row 12's first fetch.
+    let mut mem = two_pages();
+    mem.read(0xc055, cycle_at(1, 95, 50));
+    let split = render(&mem, cycle_at(2, 0, 0) - 1);
+
+    for row in 0..12 {
+        assert_eq!(text_row(&split, row), text_row(&page1, row), "row {}", row);
+    }
+    for row in 12..24 {
+        assert_eq!(text_row(&split, row), text_row(&page2, row), "row {}", row);
+    }
+}
+
+#[test]
+fn writes_show_only_on_rows_the_beam_reaches_afterwards() {
+    let mut mem = two_pages();
+    let before = render(&mem, 0);
+
+    // Mid-frame, replace the top and bottom rows with 'B'.
+    for col in 0..40 {
+        mem.write(0x0400 + col, b'B' | 0x80, cycle_at(1, 100, col as u64));
+        mem.write(0x07d0 + col, b'B' | 0x80, cycle_at(1, 100, col as u64));
+    }
+    let frame = render(&mem, cycle_at(2, 0, 0) - 1);
+    assert_eq!(text_row(&frame, 0), text_row(&before, 0));
+    assert_ne!(text_row(&frame, 23), text_row(&before, 23));
+
+    // A frame later the beam has redrawn the top row too.
+    let later = render(&mem, cycle_at(3, 0, 0) - 1);
+    assert_eq!(text_row(&later, 0), text_row(&later, 23));
+
+    // The render cycle decides what was drawn, not the bus's last access.
+    let earlier = render(&mem, cycle_at(1, 100, 0) - 1);
+    assert_eq!(earlier.pixels(), before.pixels());
+}
+
+#[test]
+fn video_log_keeps_one_frame_of_events() {
+    let mut mem = IieMemory::new();
+    mem.write(0x0400, 0x01, 10);
+    mem.write(0x0400, 0x01, 11); // unchanged byte: nothing to log
+    mem.write(0x0300, 0x01, 12); // not display memory
+    mem.read(0xc050, 13); // TEXT off
+    mem.read(0xc050, 14); // already off
+
+    let log = mem.video_log().expect("IIe memory keeps a video log");
+    let events: Vec<_> = log
+        .events()
+        .map(|(cycle, event)| (*cycle, *event))
+        .collect();
+    assert_eq!(events.len(), 2);
+    assert!(matches!(
+        events[0],
+        (
+            10,
+            VideoEvent::Write {
+                addr: 0x0400,
+                aux: false,
+                old: 0x00,
+                new: 0x01
+            }
+        )
+    ));
+    assert!(matches!(events[1], (13, VideoEvent::Switches { old, new }) if old.text && !new.text));
+
+    // Once every fetch in the window sees an event, it is part of the state.
+    mem.read(0x0000, 12 + CYCLES_PER_FRAME);
+    let log = mem.video_log().expect("IIe memory keeps a video log");
+    assert_eq!(log.events().count(), 1);
+    mem.read(0x0000, 13 + CYCLES_PER_FRAME);
+    assert_eq!(mem.video_log().expect("log").events().count(), 0);
+}
@@ -0,0 +1,154 @@
+use echo_lab::bus::Bus;
+use echo_lab::checksum::{crc32, sha1, to_hex};
+use echo_lab::config::{EchoLabConfig, SystemRomConfig};
+use echo_lab::machines::iie::memory::IieMemory;
+use echo_lab::rom::{KNOWN_ROMS, KnownRom, SystemRom};
+use echo_lab::runner::{RunOptions, boot_machine};
+use std::fs;
+
+#[test]
+fn checksums_match_standard_test_vectors() {
+    assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    assert_eq!(
+        to_hex(&sha1(b"abc")),
+        "a9993e364706816aba3e25717850c26c9cd0d89d"
+    );
+    assert_eq!(
+        to_hex(&sha1(&[0x61; 1000])),
+        "291e9a6c66994949b57ba5e650361e98fc36b1ba"
+    );
+}
+
+#[test]
+fn rejects_unexpected_sizes_and_reports_unknown_segments() {
+    let err = SystemRom::from_bytes(vec![0; 100]).expect_err("size should be rejected");
+    assert!(err.contains("unexpected image size 100"));
+
+    let rom = SystemRom::from_bytes(vec![0xea; 0x4000]).expect("16K image should load");
+    assert_eq!(rom.base(), 0xc000);
+    assert_eq!(rom.segments().len(), 2);
+    assert!(!rom.is_recognized());
+    let description = rom.describe();
+    assert!(description.contains("$C000-$DFFF: unrecognized"));
+    assert!(description.contains("$E000-$FFFF: unrecognized"));
+
+    let rom = SystemRom::from_bytes(vec![0xea; 0x3000]).expect("12K image should load");
+    assert_eq!(rom.base(), 0xd000);
+    assert_eq!(rom.segments()[0].len, 0x1000);
+}
+
+// A 12K $D000-$FFFF image known as a whole, and a look-alike with the same
+// CRC32 but another SHA-1.
+static TEST_ROMS: &[KnownRom] = &[KnownRom {
+    part: "test-12k",
+    description: "Test D000-FFFF image",
+    base: 0xd000,
+    len: 0x3000,
+    crc32: 0x7553_287d,
+    sha1: "6e6371f8d24b93b5ca8264649948b49213cdeb7d",
+}];
+static CRC_ONLY_ROMS: &[KnownRom] = &[KnownRom {
+    sha1: "0000000000000000000000000000000000000000",
+    ..TEST_ROMS[0]
+}];
+
+#[test]
+fn whole_12k_images_are_identified_by_crc32_and_sha1() {
+    let image: Vec<u8> = (0..0x3000).map(|i| (i % 251) as u8).collect();
+    let rom = SystemRom::from_bytes_with_known(image.clone(), TEST_ROMS).expect("12K image");
+    assert!(rom.is_recognized(), "{}", rom.describe());
+    assert_eq!(rom.segments().len(), 1);
+    assert!(rom.describe().contains("$D000-$FFFF: test-12k"));
+
+    let rom = SystemRom::from_bytes_with_known(image.clone(), CRC_ONLY_ROMS).expect("12K image");
+    assert!(!rom.is_recognized());
+
+    let mut patched = image;
+    patched[0x2ffc] ^= 0xff;
+    let rom = SystemRom::from_bytes_with_known(patched, TEST_ROMS).expect("12K image");
+    assert!(!rom.is_recognized());
+
+    for known in KNOWN_ROMS {
+        assert_eq!(known.sha1.len(), 40, "{}", known.part);
+        assert!(
+            known.base as usize + known.len <= 0x1_0000,
+            "{}",
+            known.part
+        );
+    }
+}
+
+#[test]
+fn configured_sha1_mismatch_is_an_error() {
+    let mut path = std::env::temp_dir();
+    path.push(format!("echolab_rom_{}.bin", std::process::id()));
+    fs::write(&path, vec![0u8; 0x4000]).expect("rom should be written");
+
+    let rom = SystemRom::from_bytes(vec![0u8; 0x4000]).expect("rom should parse");
+    let cfg = SystemRomConfig {
+        path: Some(path.to_str().expect("utf-8 path").to_owned()),
+        sha1: Some(rom.sha1_hex().to_uppercase()),
+    };
+    assert!(
+        SystemRom::load_from_config(&cfg)
+            .expect("sha1 should match")
+            .is_some()
+    );
+
+    let bad = SystemRomConfig {
+        sha1: Some("0000000000000000000000000000000000000000".to_owned()),
+        ..cfg
+    };
+    let err = SystemRom::load_from_config(&bad).expect_err("sha1 should mismatch");
+    let _ = fs::remove_file(&path);
+    assert!(err.contains("SHA-1 mismatch"));
+    assert!(err.contains(&rom.sha1_hex()));
+
+    assert_eq!(
+        SystemRom::load_from_config(&SystemRomConfig::default()),
+        Ok(None)
+    );
+}
+
+#[test]
+fn rom_flag_is_checked_against_the_configured_sha1() {
+    let mut path = std::env::temp_dir();
+    path.push(format!("echolab_rom_flag_{}.bin", std::process::id()));
+    fs::write(&path, vec![0u8; 0x4000]).expect("rom should be written");
+
+    let options = RunOptions {
+        rom_path: Some(path.to_str().expect("utf-8 path").to_owned()),
+        ..RunOptions::default()
+    };
+    let mut cfg = EchoLabConfig::default();
+    cfg.system_rom.sha1 = Some("0000000000000000000000000000000000000000".to_owned());
+    let err = boot_machine(&options, &cfg)
+        .err()
+        .expect("sha1 should mismatch");
+    cfg.system_rom.sha1 = None;
+    let booted = boot_machine(&options, &cfg);
+    let _ = fs::remove_file(&path);
+    assert!(err.contains("SHA-1 mismatch"));
+    assert!(booted.is_ok());
+}
+
+#[test]
+fn loaded_rom_is_visible_through_the_bus() {
+    let mut image = vec![0u8; 0x4000];
+    image[0x0100] = 0x11; // $C100
+    image[0x1000] = 0x22; // $D000
+    image[0x3ffc] = 0x62; // $FFFC
+    image[0x3ffd] = 0xfa;
+    let rom = SystemRom::from_bytes(image).expect("rom should parse");
+
+    let mut mem = IieMemory::new();
+    mem.load_rom(&rom);
+    assert_eq!(mem.read(0xd000, 0), 0x22);
+    assert_eq!(mem.read(0xfffc, 0), 0x62);
+    assert_eq!(mem.read(0xfffd, 0), 0xfa);
+
+    // $C100 shows internal ROM only with INTCXROM set.
+    assert_eq!(mem.read(0xc100, 0), 0x00);
+    mem.write(0xc007, 0, 0);
+    assert_eq!(mem.read(0xc100, 0), 0x11);
+}
@@ -0,0 +1,103 @@
+use echo_lab::machines::ram_init::RamInit;
+use echo_lab::runner::{RunLimit, RunOptions, run_headless};
+use echo_lab::video::{CELL_HEIGHT, CELL_WIDTH, COLOR_BLACK};
+use std::fs;
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| (*s).to_owned()).collect()
+}
+
+#[test]
+fn parse_accepts_headless_limits_and_program_loads() {
+    let options = RunOptions::parse(&args(&[
+        "--headless",
+        "--cycles",
+        "1_000",
+        "--load",
+        "$0300:prog.bin",
+        "--pc",
+        "0x0300",
+        "--ram-init",
+        "iie",
+        "--output",
+        "out.ppm",
+    ]))
+    .expect("args should parse");
+
+    assert!(options.headless);
+    assert_eq!(options.limit, Some(RunLimit::Cycles(1000)));
+    assert_eq!(options.loads, vec![(0x0300, "prog.bin".to_owned())]);
+    assert_eq!(options.start_pc, Some(0x0300));
+    assert_eq!(options.ram_init, Some(RamInit::IieStripes));
+    assert_eq!(options.output.as_deref(), Some("out.ppm"));
+}
+
+#[test]
+fn parse_rejects_missing_or_conflicting_limits() {
+    let err = RunOptions::parse(&args(&["--headless"])).expect_err("limit is required");
+    assert!(err.contains("--cycles or --frames"));
+
+    let err =
+        RunOptions::parse(&args(&["--cycles", "1", "--frames", "1"])).expect_err("limits conflict");
+    assert!(err.contains("mutually exclusive"));
+
+    let err = RunOptions::parse(&args(&["--frames"])).expect_err("value is required");
+    assert!(err.contains("missing value for --frames"));
+
+    assert_eq!(RunLimit::Frames(2).cycles(), Ok(2 * 17_030));
+    let err = RunLimit::Frames(u64::MAX)
+        .cycles()
+        .expect_err("frames overflow");
+    assert!(err.contains("--frames is too large"));
+}
+
+#[test]
+fn run_headless_rejects_limits_past_the_cycle_counter() {
+    let options = RunOptions {
+        config_path: "missing_echolab_config.toml".to_owned(),
+        headless: true,
+        limit: Some(RunLimit::Cycles(u64::MAX)),
+        ..RunOptions::default()
+    };
+    let err = run_headless(&options).expect_err("limit overflows");
+    assert!(err.contains("overflows the cycle counter"), "{}", err);
+}
+
+#[test]
+fn run_headless_executes_loaded_program_and_writes_final_frame() {
+    let dir = std::env::temp_dir().join(format!("echolab_runner_{}", std::process::id()));
+    fs::create_dir_all(&dir).expect("temp dir should be created");
+    let program = dir.join("prog.bin");
+    let output = dir.join("frame.ppm");
+
+    // LDA #$C8 ('H'); STA $0400; JMP *
+    fs::write(&program, [0xa9, 0xc8, 0x8d, 0x00, 0x04, 0x4c, 0x05, 0x03])
+        .expect("program should be written");
+
+    let options = RunOptions {
+        config_path: dir.join("missing.toml").to_str().expect("utf-8").to_owned(),
+        headless: true,
+        limit: Some(RunLimit::Frames(1)),
+        loads: vec![(0x0300, program.to_str().expect("utf-8").to_owned())],
+        start_pc: Some(0x0300),
+        output: Some(output.to_str().expect("utf-8").to_owned()),
+        ..RunOptions::default()
+    };
+
+    let summary = run_headless(&options).expect("headless run should succeed");
+    assert!(summary.cycles >= 17_030);
+    assert_eq!(summary.pc, 0x0305);
+    let cell_differs = (0..CELL_WIDTH).any(|x| {
+        (0..CELL_HEIGHT)
+            .any(|y| summary.frame.get_pixel(x, y) != summary.frame.get_pixel(x + CELL_WIDTH, y))
+    });
+    assert!(
+        cell_differs,
+        "stored character should differ from untouched RAM"
+    );
+    assert_eq!(summary.frame.get_pixel(0, 1), Some(COLOR_BLACK));
+
+    let bytes = fs::read(&output).expect("frame should be written");
+    assert!(bytes.starts_with(b"P6\n560 384\n255\n"));
+    let _ = fs::remove_dir_all(dir);
+}
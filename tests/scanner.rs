@@ -0,0 +1,159 @@
+use echo_lab::bus::Bus;
+use echo_lab::cpu::asm::assemble;
+use echo_lab::machines::iie::memory::IieMemory;
+use echo_lab::machines::{ii_plus, iie};
+use echo_lab::scheduler::is_long_cycle;
+use echo_lab::video::VideoSwitches;
+use echo_lab::video::scanner::{BeamPosition, CYCLES_PER_FRAME, next_vbl_end, next_vbl_start};
+
+fn cycle_at(line: u64, column: u64) -> u64 {
+    line * 65 + column
+}
+
+fn text_address(row: u16, col: u16) -> u16 {
+    0x0400 + 0x80 * (row % 8) + 0x28 * (row / 8) + col
+}
+
+#[test]
+fn counters_follow_the_beam_through_blanking() {
+    let start = BeamPosition::at(0);
+    assert_eq!((start.line, start.column), (0, 0));
+    assert_eq!((start.h_counter(), start.v_counter()), (0x58, 0x100));
+    assert!(start.visible());
+
+    let hbl = BeamPosition::at(40);
+    assert!(hbl.hbl() && !hbl.vbl());
+    assert_eq!(hbl.h_counter(), 0x00);
+    assert_eq!(BeamPosition::at(41).h_counter(), 0x40);
+    // The stretched cycle falls inside horizontal blanking.
+    assert!(is_long_cycle(64) && BeamPosition::at(64).hbl());
+    assert_eq!(BeamPosition::at(64).h_counter(), 0x57);
+
+    assert!(BeamPosition::at(cycle_at(192, 0)).vbl());
+    assert!(!BeamPosition::at(cycle_at(191, 64)).vbl());
+    assert_eq!(BeamPosition::at(cycle_at(255, 0)).v_counter(), 0x1ff);
+    assert_eq!(BeamPosition::at(cycle_at(256, 0)).v_counter(), 0x0fa);
+    assert_eq!(BeamPosition::at(CYCLES_PER_FRAME + 3), BeamPosition::at(3));
+
+    assert_eq!(next_vbl_start(0), cycle_at(192, 0));
+    assert_eq!(next_vbl_start(cycle_at(192, 0)), cycle_at(192, 0));
+    assert_eq!(
+        next_vbl_start(cycle_at(192, 1)),
+        CYCLES_PER_FRAME + cycle_at(192, 0)
+    );
+    assert_eq!(next_vbl_end(cycle_at(200, 0)), CYCLES_PER_FRAME);
+}
+
+#[test]
+fn scanner_addresses_match_the_text_and_hires_layouts() {
+    let text = VideoSwitches {
+        text: true,
+        ..VideoSwitches::default()
+    };
+    for row in 0..24u16 {
+        for col in 0..40u16 {
+            let line = row as u64 * 8 + 5;
+            let position = BeamPosition::at(cycle_at(line, col as u64));
+            assert_eq!(position.scanner_address(&text), text_address(row, col));
+        }
+    }
+    let page2 = VideoSwitches {
+        page2: true,
+        ..text
+    };
+    assert_eq!(BeamPosition::at(0).scanner_address(&page2), 0x0800);
+
+    let hires = VideoSwitches {
+        hires: true,
+        ..VideoSwitches::default()
+    };
+    for y in [0u16, 1, 7, 8, 63, 64, 127, 191] {
+        let position = BeamPosition::at(cycle_at(y as u64, 3));
+        let expected = 0x2000 + 0x400 * (y % 8) + 0x80 * ((y / 8) % 8) + 0x28 * (y / 64) + 3;
+        assert_eq!(position.scanner_address(&hires), expected, "line {}", y);
+    }
+
+    // Mixed mode fetches text for the last four rows.
+    let mixed = VideoSwitches {
+        mixed: true,
+        ..hires
+    };
+    let bottom = BeamPosition::at(cycle_at(160, 0));
+    assert_eq!(bottom.scanner_address(&mixed), text_address(20, 0));
+    assert_eq!(
+        BeamPosition::at(cycle_at(159, 0)).scanner_address(&mixed) & 0xe000,
+        0x2000
+    );
+
+    // The II+ adds $1000 during horizontal blanking.
+    let blanking = BeamPosition::at(50);
+    assert_eq!(
+        blanking.ii_plus_scanner_address(&text),
+        blanking.scanner_address(&text) | 0x1000
+    );
+}
+
+#[test]
+fn unconnected_reads_return_the_byte_under_the_beam() {
+    let mut mem = IieMemory::new();
+    for row in 0..24u16 {
+        for col in 0..40u16 {
+            mem.main_mut()[text_address(row, col) as usize] = (row * 40 + col) as u8;
+        }
+    }
+
+    let cycle = cycle_at(8 * 3 + 2, 17);
+    assert_eq!(mem.read(0xc0f0, cycle), (3 * 40 + 17) as u8);
+    assert_eq!(mem.peek(0xc0f0), (3 * 40 + 17) as u8);
+    assert_eq!(mem.beam_position(), BeamPosition::at(cycle));
+    // Empty slot ROM space floats too; pushbuttons only own bit 7.
+    assert_eq!(mem.read(0xc600, cycle_at(0, 1)), 1);
+    assert_eq!(mem.read(0xc061, cycle_at(0, 39)), 39);
+}
+
+#[test]
+fn rdvblbar_goes_low_exactly_when_vertical_blanking_starts() {
+    let mut mem = IieMemory::new();
+    assert_eq!(mem.read(0xc019, cycle_at(191, 64)) & 0x80, 0x80);
+    assert_eq!(mem.read(0xc019, cycle_at(192, 0)) & 0x80, 0x00);
+    assert_eq!(mem.read(0xc019, cycle_at(261, 64)) & 0x80, 0x00);
+    assert_eq!(mem.read(0xc019, CYCLES_PER_FRAME) & 0x80, 0x80);
+
+    // The II+ has no VBL flag; $C019 floats.
+    let mut plus = ii_plus::apple_ii_plus();
+    plus.memory.main_mut()[0x0400] = 0x5a;
+    assert_eq!(plus.memory.read(0xc019, 0), 0x5a);
+}
+
+#[test]
+fn vapor_lock_loop_syncs_to_a_marker_byte() {
+    let program = assemble(
+        0x0300,
+        "
+        wait:   lda $c0f0
+                cmp #$a5
+                bne wait
+                brk
+        ",
+    )
+    .expect("program should assemble");
+    let mut machine = iie::apple_iie();
+    machine.power_on();
+    machine.memory.main_mut()[0x0300..program.end() as usize].copy_from_slice(&program.bytes);
+    machine.memory.main_mut()[text_address(12, 30) as usize] = 0xa5;
+    machine.cpu.pc = 0x0300;
+
+    // Stop right after the LDA that saw the marker; its $C0F0 read is the
+    // latest bus access.
+    while machine.cpu.a != 0xa5 {
+        machine.step();
+        assert!(
+            machine.cpu.cycles < 2 * CYCLES_PER_FRAME,
+            "marker never seen"
+        );
+    }
+    assert_eq!(machine.cpu.pc, 0x0303);
+    let seen = machine.memory.beam_position();
+    assert_eq!(seen.line / 8, 12);
+    assert_eq!(seen.column, 30);
+}
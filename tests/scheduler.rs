@@ -0,0 +1,102 @@
+use echo_lab::machines::{Machine, NTSC_CYCLES_PER_FRAME, iie};
+use echo_lab::scheduler::{
+    CPU_CLOCK_HZ, MASTER_CLOCK_HZ, MASTER_TICKS_PER_FRAME, MASTER_TICKS_PER_SCANLINE, MasterClock,
+    Scheduler, cycle_to_master, is_long_cycle, master_to_cycle,
+};
+
+fn nop_machine() -> Machine {
+    let mut machine = iie::apple_iie();
+    machine.power_on();
+    // JMP $0300 forever.
+    machine.memory.main_mut()[0x0300..0x0303].copy_from_slice(&[0x4c, 0x00, 0x03]);
+    machine.cpu.pc = 0x0300;
+    machine
+}
+
+#[test]
+fn master_clock_stretches_the_last_cycle_of_each_scanline() {
+    assert_eq!(MASTER_TICKS_PER_SCANLINE, 912);
+    assert_eq!(
+        MASTER_TICKS_PER_FRAME / MASTER_TICKS_PER_SCANLINE * 65,
+        NTSC_CYCLES_PER_FRAME
+    );
+    assert!((MASTER_CLOCK_HZ / MASTER_TICKS_PER_FRAME as f64 - 59.92).abs() < 0.01);
+    assert!((CPU_CLOCK_HZ - 1_020_484.0).abs() < 1.0);
+
+    assert!(is_long_cycle(64) && !is_long_cycle(63) && !is_long_cycle(65));
+    assert_eq!(cycle_to_master(64) - cycle_to_master(63), 14);
+    assert_eq!(cycle_to_master(65) - cycle_to_master(64), 16);
+    for cycle in [0, 1, 63, 64, 65, 17_029, 17_030, 1_000_003] {
+        assert_eq!(master_to_cycle(cycle_to_master(cycle)), cycle);
+    }
+    // The long cycle is still running two ticks after a short one would end.
+    assert_eq!(master_to_cycle(cycle_to_master(64) + 15), 64);
+    assert_eq!(master_to_cycle(cycle_to_master(64) + 16), 65);
+}
+
+#[test]
+fn master_clock_carries_partial_cycles_between_advances() {
+    let mut clock = MasterClock::default();
+    assert_eq!(clock.advance(13), 0);
+    assert_eq!(clock.advance(1), 1);
+    assert_eq!(
+        clock.advance(MASTER_TICKS_PER_FRAME - 14),
+        NTSC_CYCLES_PER_FRAME
+    );
+
+    clock.sync_to_cycle(65);
+    assert_eq!(clock.ticks(), MASTER_TICKS_PER_SCANLINE);
+}
+
+#[test]
+fn scheduler_pops_events_in_due_order_and_honors_cancellation() {
+    let mut scheduler = Scheduler::new();
+    scheduler.schedule(30, "late");
+    let cancelled = scheduler.schedule(10, "cancelled");
+    scheduler.schedule(20, "first tie");
+    scheduler.schedule(20, "second tie");
+    assert!(scheduler.cancel(cancelled));
+    assert!(!scheduler.cancel(cancelled));
+
+    assert_eq!(scheduler.next_due(), Some(20));
+    assert_eq!(scheduler.pop_due(19), None);
+    assert_eq!(scheduler.pop_due(25), Some((20, "first tie")));
+    assert_eq!(scheduler.pop_due(25), Some((20, "second tie")));
+    assert_eq!(scheduler.pop_due(25), None);
+    assert_eq!(scheduler.len(), 1);
+}
+
+#[test]
+fn device_events_fire_in_cycle_time_and_can_reschedule_themselves() {
+    fn timer(machine: &mut Machine, due: u64) {
+        machine.memory.main_mut()[0x10] += 1;
+        // Late by at most one instruction.
+        assert!(machine.cpu.cycles - due < 3);
+        machine.events.schedule(due + 1_000, timer);
+    }
+
+    let mut machine = nop_machine();
+    machine.schedule_in(1_000, timer);
+    machine.run_cycles(10_500);
+    assert_eq!(machine.memory.main()[0x10], 10);
+    assert_eq!(machine.events.len(), 1);
+
+    machine.power_on();
+    assert!(machine.events.is_empty());
+}
+
+#[test]
+fn run_master_ticks_keeps_the_cpu_in_step_with_the_master_clock() {
+    let mut machine = nop_machine();
+    machine.clock.sync_to_cycle(machine.cpu.cycles);
+    let start = machine.cpu.cycles;
+
+    let mut ran = 0;
+    // A 60 Hz host delivers uneven tick counts; the total still lands on one frame.
+    for ticks in [119_000, 119_944] {
+        ran += machine.run_master_ticks(ticks);
+    }
+    let target = start + NTSC_CYCLES_PER_FRAME;
+    assert!(machine.cpu.cycles >= target && machine.cpu.cycles < target + 3);
+    assert_eq!(ran, machine.cpu.cycles - start);
+}
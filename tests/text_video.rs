@@ -1,6 +1,11 @@
+use echo_lab::bus::Bus;
+use echo_lab::machines::iie::memory::IieMemory;
 use echo_lab::screen_buffer::ScreenBuffer;
+use echo_lab::video::scanner::CYCLES_PER_FRAME;
 use echo_lab::video::{
-    CELL_WIDTH, COLOR_BLACK, COLOR_PHOSPHOR_GREEN, FRAME_HEIGHT, FRAME_WIDTH, TextVideoController,
+    CELL_HEIGHT, CELL_WIDTH, COLOR_BLACK, COLOR_PHOSPHOR_GREEN, FLASH_FRAMES, FRAME_HEIGHT,
+    FRAME_WIDTH, TEXT_COLS, TEXT_ROWS, TextAddressing, TextVideoController, VideoSwitches,
+    flash_inverted, glyph_row,
 };
 
 #[test]
@@ -10,7 +15,7 @@ fn text_video_renders_non_space_cells_on_even_scanlines_only() {
 
     let mut out = ScreenBuffer::new(FRAME_WIDTH, FRAME_HEIGHT);
     let video = TextVideoController::default();
-    video.render_frame(&ram, &mut out);
+    video.render_frame(&ram, 0, &mut out);
 
     // 'A' row 0 is 0b0001000: only the center logical pixel lit, doubled horizontally.
     assert_eq!(out.get_pixel(6, 0), Some(COLOR_PHOSPHOR_GREEN));
@@ -31,7 +36,7 @@ fn text_video_keeps_space_cells_black_even_on_active_scanlines() {
 
     let mut out = ScreenBuffer::new(FRAME_WIDTH, FRAME_HEIGHT);
     let video = TextVideoController::default();
-    video.render_frame(&ram, &mut out);
+    video.render_frame(&ram, 0, &mut out);
 
     for x in 14..28 {
         assert_eq!(out.get_pixel(x, 0), Some(COLOR_BLACK));
@@ -46,7 +51,7 @@ fn render_frame_publishes_new_frame() {
     let video = TextVideoController::default();
 
     assert_eq!(out.frame_id(), 0);
-    video.render_frame(&ram, &mut out);
+    video.render_frame(&ram, 0, &mut out);
     assert_eq!(out.frame_id(), 1);
 }
 
@@ -57,7 +62,7 @@ fn text_video_uses_correct_left_to_right_glyph_bit_order() {
 
     let mut out = ScreenBuffer::new(FRAME_WIDTH, FRAME_HEIGHT);
     let video = TextVideoController::default();
-    video.render_frame(&ram, &mut out);
+    video.render_frame(&ram, 0, &mut out);
 
     // 'F' row 1 in this ROM is 0b0000010 for the vertical stroke; with correct
     // orientation this appears near the left edge (logical x=1, doubled to x=2/3).
@@ -72,7 +77,7 @@ fn text_video_preserves_h_middle_bar_with_doubled_y_mapping() {
 
     let mut out = ScreenBuffer::new(FRAME_WIDTH, FRAME_HEIGHT);
     let video = TextVideoController::default();
-    video.render_frame(&ram, &mut out);
+    video.render_frame(&ram, 0, &mut out);
 
     // 'H' row 3 is 0b0111110 in this ROM and should appear at y = 3 * 2.
     assert_eq!(out.get_pixel(2, 6), Some(COLOR_PHOSPHOR_GREEN));
@@ -88,7 +93,7 @@ fn text_video_d_has_hard_left_edge_and_differs_from_o() {
 
     let mut out = ScreenBuffer::new(FRAME_WIDTH, FRAME_HEIGHT);
     let video = TextVideoController::default();
-    video.render_frame(&ram, &mut out);
+    video.render_frame(&ram, 0, &mut out);
 
     let d_cell_x = 0usize;
     let o_cell_x = CELL_WIDTH;
@@ -99,3 +104,137 @@ fn text_video_d_has_hard_left_edge_and_differs_from_o() {
     });
     assert!(differs, "D and O should render as distinct glyphs");
 }
+
+fn render_iie(mem: &IieMemory, cycle: u64) -> ScreenBuffer {
+    let mut out = ScreenBuffer::new(FRAME_WIDTH, FRAME_HEIGHT);
+    TextVideoController::default().render_frame(mem, cycle, &mut out);
+    out
+}
+
+fn first_cell(frame: &ScreenBuffer) -> Vec<u32> {
+    (0..CELL_HEIGHT)
+        .flat_map(|y| (0..CELL_WIDTH).map(move |x| (x, y)))
+        .map(|(x, y)| frame.get_pixel(x, y).unwrap_or(COLOR_BLACK))
+        .collect()
+}
+
+#[test]
+fn iie_primary_set_draws_normal_high_codes_and_inverse_low_codes() {
+    let mut mem = IieMemory::new();
+    mem.main_mut()[0x0400] = b'A' | 0x80;
+    let normal = render_iie(&mem, 0);
+    assert_eq!(normal.get_pixel(6, 0), Some(COLOR_PHOSPHOR_GREEN));
+    assert_eq!(normal.get_pixel(0, 0), Some(COLOR_BLACK));
+
+    // $01 is an inverse 'A': lit background, dark stroke.
+    mem.main_mut()[0x0400] = 0x01;
+    let inverse = render_iie(&mem, 0);
+    assert_eq!(inverse.get_pixel(0, 0), Some(COLOR_PHOSPHOR_GREEN));
+    assert_eq!(inverse.get_pixel(6, 0), Some(COLOR_BLACK));
+    // Odd scanlines stay black either way.
+    assert_eq!(inverse.get_pixel(0, 1), Some(COLOR_BLACK));
+}
+
+#[test]
+fn iie_flashing_codes_toggle_every_sixteen_frames() {
+    assert!(!flash_inverted(0));
+    assert!(!flash_inverted(FLASH_FRAMES * CYCLES_PER_FRAME - 1));
+    assert!(flash_inverted(FLASH_FRAMES * CYCLES_PER_FRAME));
+    assert!(!flash_inverted(2 * FLASH_FRAMES * CYCLES_PER_FRAME));
+
+    let mut mem = IieMemory::new();
+    mem.main_mut()[0x0400] = b'A';
+    mem.main_mut()[0x0401] = 0x01;
+    mem.main_mut()[0x0402] = b'A' | 0x80;
+    let steady = render_iie(&mem, 2 * CYCLES_PER_FRAME);
+    let flashed = render_iie(&mem, (FLASH_FRAMES + 2) * CYCLES_PER_FRAME);
+
+    let cell = |frame: &ScreenBuffer, col: usize| -> Vec<u32> {
+        (0..CELL_WIDTH)
+            .map(|x| frame.get_pixel(col * CELL_WIDTH + x, 0).unwrap_or(0))
+            .collect()
+    };
+    // Shown inverse, then normal; the inverse and normal codes never change.
+    assert_eq!(cell(&steady, 0), cell(&steady, 1));
+    assert_eq!(cell(&flashed, 0), cell(&flashed, 2));
+    assert_eq!(cell(&steady, 1), cell(&flashed, 1));
+    assert_eq!(cell(&steady, 2), cell(&flashed, 2));
+}
+
+#[test]
+fn iie_altcharset_swaps_flashing_codes_for_mousetext() {
+    let mut mem = IieMemory::new();
+    mem.main_mut()[0x0400] = 0x40;
+    let primary = render_iie(&mem, 0);
+
+    mem.write(0xc00f, 0, 0);
+    let alternate = render_iie(&mem, 0);
+    assert_ne!(first_cell(&primary), first_cell(&alternate));
+    // MouseText glyphs come straight from the alternate bank and never flash.
+    for line in 0..8 {
+        let on = VideoSwitches {
+            altcharset: true,
+            ..VideoSwitches::default()
+        };
+        assert_eq!(
+            glyph_row(0x40, Some(&on), false, line),
+            glyph_row(0x40, Some(&on), true, line)
+        );
+    }
+
+    // Inverse uppercase is shared by both sets.
+    mem.main_mut()[0x0400] = 0x01;
+    let alt_inverse = render_iie(&mem, 0);
+    mem.write(0xc00e, 0, 0);
+    assert_eq!(first_cell(&alt_inverse), first_cell(&render_iie(&mem, 0)));
+}
+
+fn lit_cells(frame: &ScreenBuffer) -> Vec<(usize, usize)> {
+    let mut cells = Vec::new();
+    for row in 0..TEXT_ROWS {
+        for col in 0..TEXT_COLS {
+            let lit = (0..CELL_HEIGHT).any(|dy| {
+                (0..CELL_WIDTH).any(|dx| {
+                    frame.get_pixel(col * CELL_WIDTH + dx, row * CELL_HEIGHT + dy)
+                        != Some(COLOR_BLACK)
+                })
+            });
+            if lit {
+                cells.push((row, col));
+            }
+        }
+    }
+    cells
+}
+
+#[test]
+fn text_rows_follow_the_iie_interleave_and_skip_screen_holes() {
+    assert_eq!(TextAddressing::Interleaved.row_address(0x0400, 1), 0x0480);
+    assert_eq!(TextAddressing::Interleaved.row_address(0x0400, 8), 0x0428);
+    assert_eq!(TextAddressing::Interleaved.row_address(0x0800, 23), 0x0bd0);
+
+    let mut ram = [0x20u8; 65536];
+    ram[0x0480] = b'A';
+    ram[0x0428 + 5] = b'B';
+    ram[0x07d0 + 39] = b'C';
+    // Screen holes are never fetched.
+    ram[0x0478..0x0480].fill(b'X');
+    ram[0x07f8..0x0800].fill(b'X');
+
+    let mut out = ScreenBuffer::new(FRAME_WIDTH, FRAME_HEIGHT);
+    TextVideoController::default().render_frame(&ram, 0, &mut out);
+    assert_eq!(lit_cells(&out), vec![(1, 0), (8, 5), (23, 39)]);
+}
+
+#[test]
+fn linear_text_addressing_packs_rows_back_to_back() {
+    let mut ram = [0x20u8; 65536];
+    ram[0x0400 + 40] = b'A';
+    ram[0x0400 + 23 * 40 + 39] = b'C';
+
+    let mut out = ScreenBuffer::new(FRAME_WIDTH, FRAME_HEIGHT);
+    TextVideoController::default()
+        .with_text_addressing(TextAddressing::Linear)
+        .render_frame(&ram, 0, &mut out);
+    assert_eq!(lit_cells(&out), vec![(1, 0), (23, 39)]);
+}
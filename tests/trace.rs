@@ -0,0 +1,112 @@
+use echo_lab::cpu::Cpu;
+use echo_lab::cpu::asm::assemble;
+use echo_lab::machines::iie;
+use echo_lab::runner::{RunLimit, RunOptions, run_headless};
+use echo_lab::trace::{TraceEvent, TraceFormat, Tracer, trace_step};
+use std::fs;
+
+fn load(source: &str) -> (Cpu, [u8; 65536]) {
+    let program = assemble(0x0300, source).expect("program should assemble");
+    let mut mem = [0u8; 65536];
+    mem[0x0300..program.end() as usize].copy_from_slice(&program.bytes);
+    let mut cpu = Cpu::new();
+    cpu.pc = 0x0300;
+    (cpu, mem)
+}
+
+#[test]
+fn native_trace_lists_registers_before_execution_and_bus_accesses() {
+    let (mut cpu, mut mem) = load("lda #$42\nsta $2000\ninc $10");
+    let mut tracer = Tracer::in_memory(TraceFormat::Native);
+    for _ in 0..3 {
+        tracer.step_cpu(&mut cpu, &mut mem).expect("memory sink");
+    }
+
+    assert_eq!(
+        tracer.lines(),
+        [
+            "         0  0300  A9 42     LDA #$42        A=00 X=00 Y=00 P=24 SP=FD  r0300:A9 r0301:42",
+            "         2  0302  8D 00 20  STA $2000       A=42 X=00 Y=00 P=24 SP=FD  r0302:8D r0303:00 r0304:20 w2000:42",
+            "         6  0305  E6 10     INC $10         A=42 X=00 Y=00 P=24 SP=FD  r0305:E6 r0306:10 r0010:00 r0010:00 w0010:01",
+        ]
+    );
+}
+
+#[test]
+fn nestest_trace_uses_reference_column_layout() {
+    let (mut cpu, mut mem) = load("jmp $c5f5");
+    cpu.cycles = 7;
+    let mut tracer = Tracer::in_memory(TraceFormat::Nestest);
+    tracer.step_cpu(&mut cpu, &mut mem).expect("memory sink");
+
+    let line = &tracer.lines()[0];
+    assert_eq!(
+        line,
+        "0300  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD CYC:7"
+    );
+    assert_eq!(line.find("A:"), Some(48));
+}
+
+#[test]
+fn interrupts_are_traced_as_their_own_events() {
+    let (mut cpu, mut mem) = load("cli\nnop");
+    mem[0xfffe] = 0x00;
+    mem[0xffff] = 0x04;
+    let (first, _) = trace_step(&mut cpu, &mut mem);
+    assert!(matches!(first.event, TraceEvent::Instruction(i) if i.mnemonic() == "CLI"));
+
+    cpu.set_irq_line(0, true);
+    let (record, cycles) = trace_step(&mut cpu, &mut mem);
+    assert_eq!(record.event, TraceEvent::Irq);
+    assert_eq!(cycles, 7);
+    assert_eq!(cpu.pc, 0x0400);
+    assert!(
+        record
+            .format(TraceFormat::Native)
+            .contains("0301            *IRQ")
+    );
+    assert_eq!(record.accesses.iter().filter(|a| a.write).count(), 3);
+}
+
+#[test]
+fn headless_runner_writes_trace_file() {
+    let options = RunOptions::parse(&[
+        "--headless".to_owned(),
+        "--cycles".to_owned(),
+        "10".to_owned(),
+        "--trace-format".to_owned(),
+        "nestest".to_owned(),
+        "--trace".to_owned(),
+        "t.log".to_owned(),
+    ])
+    .expect("args should parse");
+    assert_eq!(options.trace.as_deref(), Some("t.log"));
+    assert_eq!(options.trace_format, TraceFormat::Nestest);
+    assert!(TraceFormat::parse("mame").is_err());
+
+    let dir = std::env::temp_dir().join(format!("echolab_trace_{}", std::process::id()));
+    fs::create_dir_all(&dir).expect("temp dir should be created");
+    let path = dir.join("trace.log");
+    let options = RunOptions {
+        config_path: dir.join("missing.toml").to_str().expect("utf-8").to_owned(),
+        headless: true,
+        limit: Some(RunLimit::Cycles(20)),
+        start_pc: Some(0x0300),
+        trace: Some(path.to_str().expect("utf-8").to_owned()),
+        ..RunOptions::default()
+    };
+    let summary = run_headless(&options).expect("traced run should succeed");
+
+    // Zeroed RAM executes BRK at $0300 through an unloaded ROM's zero vector.
+    let text = fs::read_to_string(&path).expect("trace should be written");
+    assert!(text.starts_with("         7  0300  00        BRK"));
+    assert!(text.lines().count() >= 2);
+    assert!(summary.cycles >= 27);
+
+    let mut machine = iie::apple_iie();
+    machine.power_on();
+    let mut tracer = Tracer::in_memory(TraceFormat::Native);
+    tracer.run_cycles(&mut machine, 20).expect("memory sink");
+    assert!(!tracer.lines().is_empty());
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use common::{SETTLED, iie_with};
+use echo_lab::bus::Bus;
+use echo_lab::machines::iie::memory::IieMemory;
+use echo_lab::runner::RunOptions;
+use echo_lab::screen_buffer::ScreenBuffer;
+use echo_lab::video::{
+    COLOR_BLACK, COLOR_PHOSPHOR_GREEN, FRAME_HEIGHT, FRAME_WIDTH, FrameGeometry, GLYPH_WIDTH,
+    TextVideoController, VideoSwitches, glyph_row,
+};
+
+fn render(mem: &IieMemory, cycle: u64, geometry: FrameGeometry) -> ScreenBuffer {
+    common::render(
+        mem,
+        cycle,
+        TextVideoController::default().with_geometry(geometry),
+    )
+}
+
+fn lit(frame: &ScreenBuffer, x: usize, y: usize) -> bool {
+    frame.get_pixel(x, y) == Some(COLOR_PHOSPHOR_GREEN)
+}
+
+#[test]
+fn eighty_columns_interleave_aux_and_main_glyphs_at_native_width() {
+    let mut mem = iie_with(&[0xc00d]); // 80COL on
+    mem.main_mut()[0x0400..0x0800].fill(b' ' | 0x80);
+    mem.aux_mut()[0x0400..0x0800].fill(b' ' | 0x80);
+    mem.aux_mut()[0x0400] = b'H' | 0x80;
+    mem.main_mut()[0x0400] = b'I' | 0x80;
+    mem.aux_mut()[0x0427] = b'E' | 0x80;
+    let frame = render(&mem, SETTLED, FrameGeometry::Scanlines);
+    let switches = mem.video_switches().expect("IIe switches");
+    assert!(switches.col80);
+    for line in 0..8u16 {
+        let h = glyph_row(b'H' | 0x80, Some(&switches), false, line);
+        let i = glyph_row(b'I' | 0x80, Some(&switches), false, line);
+        let e = glyph_row(b'E' | 0x80, Some(&switches), false, line);
+        for x in 0..GLYPH_WIDTH {
+            let y = line as usize * 2;
+            assert_eq!(
+                lit(&frame, x, y),
+                (h >> x) & 1 != 0,
+                "H dot {} line {}",
+                x,
+                line
+            );
+            assert_eq!(lit(&frame, GLYPH_WIDTH + x, y), (i >> x) & 1 != 0);
+            // Column 78 is the aux half of the last 40-column cell.
+            assert_eq!(lit(&frame, 78 * GLYPH_WIDTH + x, y), (e >> x) & 1 != 0);
+            assert!(!lit(&frame, 79 * GLYPH_WIDTH + x, y));
+        }
+    }
+}
+
+#[test]
+fn forty_columns_ignore_aux_and_double_each_dot() {
+    let mut mem = IieMemory::new();
+    mem.main_mut()[0x0400] = b'I' | 0x80;
+    mem.aux_mut()[0x0400] = b'H' | 0x80;
+
+    let frame = render(&mem, 0, FrameGeometry::Scanlines);
+    let main_only = glyph_row(b'I' | 0x80, Some(&VideoSwitches::default()), false, 1);
+    for x in 0..GLYPH_WIDTH * 2 {
+        assert_eq!(lit(&frame, x, 2), (main_only >> (x / 2)) & 1 != 0);
+    }
+}
+
+#[test]
+fn geometries_map_scanlines_to_output_rows() {
+    assert_eq!(
+        FrameGeometry::Scanlines.dimensions(),
+        (FRAME_WIDTH, FRAME_HEIGHT)
+    );
+    assert_eq!(FrameGeometry::LineDoubled.dimensions(), (560, 384));
+    assert_eq!(FrameGeometry::Native.dimensions(), (560, 192));
+    assert_eq!(
+        FrameGeometry::parse("Doubled"),
+        Ok(FrameGeometry::LineDoubled)
+    );
+    assert!(FrameGeometry::parse("wide").is_err());
+
+    // A solid inverse space lights every dot of the cell's scanlines.
+    let mut mem = IieMemory::new();
+    mem.main_mut()[0x0400] = 0x20;
+    let scanlines = render(&mem, 0, FrameGeometry::Scanlines);
+    let doubled = render(&mem, 0, FrameGeometry::LineDoubled);
+    let native = render(&mem, 0, FrameGeometry::Native);
+    for line in 0..7 {
+        assert!(lit(&scanlines, 0, line * 2));
+        assert_eq!(scanlines.get_pixel(0, line * 2 + 1), Some(COLOR_BLACK));
+        assert!(lit(&doubled, 0, line * 2) && lit(&doubled, 0, line * 2 + 1));
+        assert!(lit(&native, 0, line));
+    }
+}
+
+#[test]
+fn runner_geometry_flag_sets_the_saved_frame_size() {
+    let args: Vec<String> = ["--headless", "--cycles", "1", "--geometry", "native"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+    let options = RunOptions::parse(&args).expect("options should parse");
+    assert_eq!(options.geometry, FrameGeometry::Native);
+
+    let bad: Vec<String> = ["--headless", "--cycles", "1", "--geometry", "wide"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+    assert!(RunOptions::parse(&bad).is_err());
+}